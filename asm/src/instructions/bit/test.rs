@@ -57,7 +57,7 @@ impl const SetRegister for BitwiseTest {
         builder.extended(0x47, Self::new(L, BitPosition::ZERO), 2, 2);
 
         // BIT 0, (HL)
-        builder.extended(0x46, Self::new(Pointer(Pair::HL), BitPosition::ZERO), 2, 4);
+        builder.extended(0x46, Self::new(Pointer(Pair::HL), BitPosition::ZERO), 2, 3);
 
         // BIT 1, r8
         builder.extended(0x48, Self::new(A, BitPosition::ONE), 2, 2);
@@ -69,7 +69,7 @@ impl const SetRegister for BitwiseTest {
         builder.extended(0x4F, Self::new(L, BitPosition::ONE), 2, 2);
 
         // BIT 1, (HL)
-        builder.extended(0x4E, Self::new(Pointer(Pair::HL), BitPosition::ONE), 2, 4);
+        builder.extended(0x4E, Self::new(Pointer(Pair::HL), BitPosition::ONE), 2, 3);
 
         // BIT 2, r8
         builder.extended(0x50, Self::new(A, BitPosition::TWO), 2, 2);
@@ -81,7 +81,7 @@ impl const SetRegister for BitwiseTest {
         builder.extended(0x57, Self::new(L, BitPosition::TWO), 2, 2);
 
         // BIT 2, (HL)
-        builder.extended(0x56, Self::new(Pointer(Pair::HL), BitPosition::TWO), 2, 4);
+        builder.extended(0x56, Self::new(Pointer(Pair::HL), BitPosition::TWO), 2, 3);
 
         // BIT 3, r8
         builder.extended(0x58, Self::new(A, BitPosition::THREE), 2, 2);
@@ -93,7 +93,7 @@ impl const SetRegister for BitwiseTest {
         builder.extended(0x5F, Self::new(L, BitPosition::THREE), 2, 2);
 
         // BIT 3, (HL)
-        builder.extended(0x5E, Self::new(Pointer(Pair::HL), BitPosition::THREE), 2, 4);
+        builder.extended(0x5E, Self::new(Pointer(Pair::HL), BitPosition::THREE), 2, 3);
 
         // BIT 4, r8
         builder.extended(0x60, Self::new(A, BitPosition::FOUR), 2, 2);
@@ -105,7 +105,7 @@ impl const SetRegister for BitwiseTest {
         builder.extended(0x67, Self::new(L, BitPosition::FOUR), 2, 2);
 
         // BIT 4, (HL)
-        builder.extended(0x66, Self::new(Pointer(Pair::HL), BitPosition::FOUR), 2, 4);
+        builder.extended(0x66, Self::new(Pointer(Pair::HL), BitPosition::FOUR), 2, 3);
 
         // BIT 5, r8
         builder.extended(0x68, Self::new(A, BitPosition::FIVE), 2, 2);
@@ -117,7 +117,7 @@ impl const SetRegister for BitwiseTest {
         builder.extended(0x6F, Self::new(L, BitPosition::FIVE), 2, 2);
 
         // BIT 5, (HL)
-        builder.extended(0x6E, Self::new(Pointer(Pair::HL), BitPosition::FIVE), 2, 4);
+        builder.extended(0x6E, Self::new(Pointer(Pair::HL), BitPosition::FIVE), 2, 3);
 
         // BIT 6, r8
         builder.extended(0x70, Self::new(A, BitPosition::SIX), 2, 2);
@@ -129,7 +129,7 @@ impl const SetRegister for BitwiseTest {
         builder.extended(0x77, Self::new(L, BitPosition::SIX), 2, 2);
 
         // BIT 6, (HL)
-        builder.extended(0x76, Self::new(Pointer(Pair::HL), BitPosition::SIX), 2, 4);
+        builder.extended(0x76, Self::new(Pointer(Pair::HL), BitPosition::SIX), 2, 3);
 
         // BIT 7, r8
         builder.extended(0x78, Self::new(A, BitPosition::SEVEN), 2, 2);
@@ -141,7 +141,7 @@ impl const SetRegister for BitwiseTest {
         builder.extended(0x7F, Self::new(L, BitPosition::SEVEN), 2, 2);
 
         // BIT 7, (HL)
-        builder.extended(0x7E, Self::new(Pointer(Pair::HL), BitPosition::SEVEN), 2, 4);
+        builder.extended(0x7E, Self::new(Pointer(Pair::HL), BitPosition::SEVEN), 2, 3);
     }
 }
 
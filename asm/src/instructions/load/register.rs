@@ -152,7 +152,7 @@ impl const SetRegister for RegisterLoad {
 
         // Others
         builder.base(0xFA, Self::new(A, Pointer(WordData::new())), 3, 4);
-        builder.base(0xF2, Self::new(A, Pointer(C)), 2, 2);
+        builder.base(0xF2, Self::new(A, Pointer(C)), 1, 2);
         builder.base(0xF0, Self::new(A, Pointer(ByteData::new())), 2, 3);
     }
 }
@@ -17,7 +17,7 @@ impl const From<RegisterPointerLoad> for InstructionKind {
 
 impl const SetRegister for RegisterPointerLoad {
     fn register(builder: &mut Builder) {
-        builder.base(0xE2, Self, 2, 2);
+        builder.base(0xE2, Self, 1, 2);
     }
 }
 
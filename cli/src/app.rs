@@ -1,12 +1,19 @@
 use crate::{
     auto::{start_auto_tick, AutoTick},
-    command::{self, Command},
+    cli::Cli,
+    command::{self, Command, RtcCommand},
 };
 use crossterm::event::{self, Event, KeyCode};
 use gb_rs_asm::operations::OperationKind;
-use gb_rs_core::{cpu::inspector::Message, memory::cartridge::mbc::ControllerType, Hardware};
+use gb_rs_core::{
+    cpu::{inspector::Message, StepOutcome},
+    memory::{
+        cartridge::mbc::{mbc3::rtc::RtcTime, ControllerType},
+        serial::LinkCable,
+    },
+    Hardware,
+};
 use std::{
-    path::Path,
     sync::mpsc::{Receiver, TryRecvError},
     time::Duration,
 };
@@ -27,8 +34,27 @@ impl App {
     const MAX_OPERATION_LOG_LEN: usize = 50;
     const MAX_COMMAND_LOG_LEN: usize = 50;
 
-    pub fn from_file(cart_file: &Path) -> Result<Self> {
-        let mut hardware = Hardware::from_file(cart_file)?;
+    pub fn from_cli(cli: &Cli, link_cable: Box<dyn LinkCable>) -> Result<Self> {
+        let mut builder = Hardware::builder();
+
+        if let Some(model) = cli.model {
+            builder = builder.model(model);
+        }
+
+        if let Some(boot_rom) = &cli.boot_rom {
+            builder = builder.boot_rom_file(boot_rom)?;
+        }
+
+        if let Some(save_interval) = cli.save_interval {
+            builder = builder.save_interval(save_interval);
+        }
+
+        if let Some(rtc_clock) = cli.rtc_clock {
+            builder = builder.rtc_clock(rtc_clock);
+        }
+
+        let mut hardware = builder.build_from_file(&cli.cart_file)?;
+        hardware.memory.connect_link_cable(link_cable);
 
         Ok(Self {
             inspector_rx: hardware.cpu.inspect(),
@@ -43,15 +69,28 @@ impl App {
 
     pub fn run(&mut self) -> Result<Outcome> {
         if let Some(AutoTick { receiver, .. }) = &self.auto_tick {
+            let mut locked = false;
+
             loop {
                 match receiver.try_recv() {
-                    Ok(_) => self.hardware.step(),
+                    Ok(_) => {
+                        if let StepOutcome::Locked { .. } = self.hardware.step()? {
+                            locked = true;
+                            break;
+                        }
+                    }
                     Err(e) => match e {
                         TryRecvError::Disconnected => return Err(Error::AutoTick),
                         _ => break,
                     },
                 }
             }
+
+            // There's no reason to keep ticking a CPU that's locked up; it can only be recovered
+            // with a reset.
+            if locked {
+                self.stop_auto_tick()?;
+            }
         }
 
         if let Some(outcome) = self.handle_input()? {
@@ -139,10 +178,10 @@ impl App {
         let output = match command {
             Command::Quit => return Ok(Outcome::Quit),
             Command::Reset => return Ok(Outcome::Reset),
-            Command::Next => {
-                self.hardware.step();
-                None
-            }
+            Command::Next => match self.hardware.step()? {
+                StepOutcome::Locked { pc } => Some(CommandOutput::Locked { pc }),
+                _ => None,
+            },
             Command::CartInfo => Some(CommandOutput::CartInfo {
                 title: self.hardware.memory.cartridge.title.clone(),
                 mbc_kind: self
@@ -191,6 +230,7 @@ impl App {
                     value: inner.value,
                 })
             }
+            Command::Rtc(ref inner) => Some(self.on_rtc_command(inner)),
         };
 
         if Self::is_history_allowed(&command) {
@@ -203,6 +243,25 @@ impl App {
         Ok(Outcome::Success)
     }
 
+    fn on_rtc_command(&mut self, command: &RtcCommand) -> CommandOutput {
+        let Some(rtc) = self.hardware.memory.cartridge.rtc_mut() else {
+            return CommandOutput::NoRtc;
+        };
+
+        match *command {
+            RtcCommand::Show => (),
+            RtcCommand::Set(time) => rtc.set_time(time),
+            RtcCommand::Advance(duration) => rtc.advance(duration),
+            RtcCommand::Carry(carry) => rtc.set_day_carry(carry),
+        }
+
+        CommandOutput::Rtc {
+            time: rtc.time(),
+            halted: rtc.is_halted(),
+            carry: rtc.has_day_carry(),
+        }
+    }
+
     fn on_inspector_message(&mut self, message: Message) {
         if let Message::Operation { pc, op } = message {
             self.push_operation(CpuStepResult {
@@ -281,6 +340,9 @@ pub enum CommandOutput {
         title: String,
         mbc_kind: ControllerType,
     },
+    Locked {
+        pc: u16,
+    },
     ReadByte {
         address: u16,
         value: u8,
@@ -297,4 +359,10 @@ pub enum CommandOutput {
         address: u16,
         value: u16,
     },
+    Rtc {
+        time: RtcTime,
+        halted: bool,
+        carry: bool,
+    },
+    NoRtc,
 }
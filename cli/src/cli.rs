@@ -1,8 +1,61 @@
 use clap::Parser;
-use std::path::PathBuf;
+use gb_rs_core::{
+    common::model::Model,
+    memory::{
+        cartridge::mbc::mbc3::clock::RtcClock,
+        serial::{Disconnected, LinkAddress, LinkCable, Socket},
+    },
+};
+use std::{io, path::PathBuf, time::Duration};
 
 #[derive(Debug, Parser)]
 #[command(author, version, about)]
 pub struct Cli {
     pub cart_file: PathBuf,
+
+    /// The hardware model to emulate (dmg0, dmg, mgb, sgb, sgb2, cgb or agb). Defaults to cgb for
+    /// Game Boy Color cartridges, and dmg otherwise.
+    #[arg(long)]
+    pub model: Option<Model>,
+
+    /// A boot ROM to run before the cartridge. It must match the model being emulated.
+    #[arg(long)]
+    pub boot_rom: Option<PathBuf>,
+
+    /// How often to check for changes to battery-backed cartridge RAM, in seconds of emulated
+    /// time. Changes are written to the cartridge's .sav file.
+    #[arg(long, value_name = "SECONDS", value_parser = parse_seconds)]
+    pub save_interval: Option<Duration>,
+
+    /// What drives the cartridge's real time clock: emulated (the default) follows emulation
+    /// speed, so it keeps in step with pausing and fast-forwarding, while host follows the
+    /// computer's clock.
+    #[arg(long, value_name = "CLOCK")]
+    pub rtc_clock: Option<RtcClock>,
+
+    /// Links to another emulator by waiting for it to connect to ADDRESS, which is either
+    /// <host>:<port> for TCP or unix:<path> for a Unix domain socket.
+    #[arg(long, value_name = "ADDRESS", conflicts_with = "link_connect")]
+    pub link_listen: Option<LinkAddress>,
+
+    /// Links to another emulator that's listening on ADDRESS.
+    #[arg(long, value_name = "ADDRESS")]
+    pub link_connect: Option<LinkAddress>,
+}
+
+impl Cli {
+    /// Sets up the link cable requested on the command line, which is left disconnected if
+    /// neither --link-listen nor --link-connect was passed.
+    pub fn link_cable(&self) -> io::Result<Box<dyn LinkCable>> {
+        Ok(match (&self.link_listen, &self.link_connect) {
+            (Some(address), _) => Box::new(Socket::listen(address)?),
+            (_, Some(address)) => Box::new(Socket::connect(address)?),
+            _ => Box::new(Disconnected),
+        })
+    }
+}
+
+fn parse_seconds(s: &str) -> Result<Duration, String> {
+    let seconds: f64 = s.parse().map_err(|e| format!("{e}"))?;
+    Duration::try_from_secs_f64(seconds).map_err(|e| format!("{e}"))
 }
@@ -1,11 +1,13 @@
 pub use auto::*;
 pub use read::*;
+pub use rtc::*;
 pub use write::*;
 
 use std::{fmt::Display, num::ParseIntError, str::FromStr};
 
 mod auto;
 mod read;
+mod rtc;
 mod write;
 
 #[derive(Debug, Clone)]
@@ -19,6 +21,7 @@ pub enum Command {
     ReadWord(ReadWordCommand),
     WriteByte(WriteByteCommand),
     WriteWord(WriteWordCommand),
+    Rtc(RtcCommand),
 }
 
 impl FromStr for Command {
@@ -40,6 +43,7 @@ impl FromStr for Command {
             "rw" | "read-word" => ReadWordCommand::from_args(args),
             "wb" | "write-byte" => WriteByteCommand::from_args(args),
             "ww" | "write-word" => WriteWordCommand::from_args(args),
+            "rtc" => RtcCommand::from_args(args),
             _ => Err(Error::Unrecognized),
         }
     }
@@ -72,6 +76,10 @@ impl Display for Command {
                 f.write_str("write-word ")?;
                 f.write_fmt(format_args!("{inner}"))
             }
+            Self::Rtc(inner) => {
+                f.write_str("rtc ")?;
+                f.write_fmt(format_args!("{inner}"))
+            }
         }
     }
 }
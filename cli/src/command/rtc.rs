@@ -0,0 +1,83 @@
+use super::*;
+use gb_rs_core::memory::cartridge::mbc::mbc3::rtc::RtcTime;
+use std::time::Duration;
+
+/// Inspects or changes the cartridge's real time clock.
+///
+/// - `rtc [show]` shows the current time.
+/// - `rtc set <days> <hours> <minutes> [seconds]` sets the time.
+/// - `rtc advance <amount>[s|m|h|d]` moves the time forward, in seconds by default.
+/// - `rtc carry <0|1>` clears or sets the day carry flag.
+#[derive(Debug, Clone)]
+pub enum RtcCommand {
+    Show,
+    Set(RtcTime),
+    Advance(Duration),
+    Carry(bool),
+}
+
+impl FromArgs for RtcCommand {
+    fn from_args(mut args: Vec<&str>) -> Result<Command> {
+        let command = match args.pop().map(str::to_lowercase).as_deref() {
+            None | Some("show") => Self::Show,
+            Some("set") => {
+                let days = parse_arg(args.pop())?;
+                let hours = parse_arg(args.pop())?;
+                let minutes = parse_arg(args.pop())?;
+                let seconds = match args.pop() {
+                    Some(seconds) => parse_arg(Some(seconds))?,
+                    None => 0,
+                };
+
+                Self::Set(RtcTime {
+                    days,
+                    hours,
+                    minutes,
+                    seconds,
+                })
+            }
+            Some("advance") => Self::Advance(parse_duration(args.pop())?),
+            Some("carry") => match parse_arg::<u8>(args.pop())? {
+                0 => Self::Carry(false),
+                1 => Self::Carry(true),
+                _ => return Err(Error::InvalidArgument),
+            },
+            Some(_) => return Err(Error::InvalidArgument),
+        };
+
+        Ok(Command::Rtc(command))
+    }
+}
+
+impl Display for RtcCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Show => f.write_str("show"),
+            Self::Set(time) => f.write_fmt(format_args!(
+                "set {} {} {} {}",
+                time.days, time.hours, time.minutes, time.seconds
+            )),
+            Self::Advance(duration) => f.write_fmt(format_args!("advance {}s", duration.as_secs())),
+            Self::Carry(carry) => f.write_fmt(format_args!("carry {}", *carry as u8)),
+        }
+    }
+}
+
+fn parse_duration(input: Option<&str>) -> Result<Duration> {
+    let Some(input) = input else {
+        return Err(Error::MissingArgument);
+    };
+
+    let (fragment, unit) = match input.char_indices().last() {
+        Some((i, 's')) => (&input[..i], 1),
+        Some((i, 'm')) => (&input[..i], 60),
+        Some((i, 'h')) => (&input[..i], 3600),
+        Some((i, 'd')) => (&input[..i], 86_400),
+        _ => (input, 1),
+    };
+
+    let value: u64 = fragment.parse().map_err(|_| Error::InvalidArgument)?;
+    let seconds = value.checked_mul(unit).ok_or(Error::InvalidArgument)?;
+
+    Ok(Duration::from_secs(seconds))
+}
@@ -13,7 +13,7 @@ mod ui;
 
 fn main() -> Result<(), Box<dyn Error>> {
     let cli = Cli::parse();
-    let mut app = App::from_file(&cli.cart_file)?;
+    let mut app = App::from_cli(&cli, cli.link_cable()?)?;
 
     let mut terminal = ui::create()?;
     ui::set_up(&mut terminal)?;
@@ -23,9 +23,17 @@ fn main() -> Result<(), Box<dyn Error>> {
 
         match app.run() {
             Ok(Outcome::Reset) => {
-                app = App::from_file(&cli.cart_file)?;
+                // The save file needs to be up to date before it's loaded by the new hardware.
+                app.hardware.save()?;
+
+                // The link cable stays plugged in, rather than reconnecting to the other emulator.
+                let link_cable = app.hardware.disconnect_link_cable();
+                app = App::from_cli(&cli, link_cable)?;
+            }
+            Ok(Outcome::Quit) => {
+                app.hardware.save()?;
+                break;
             }
-            Ok(Outcome::Quit) => break,
             Err(e) => return Err(Box::new(e)),
             _ => (),
         };
@@ -110,6 +110,7 @@ impl AsLogItem for CommandOutput {
                     Span::styled(format!("{mbc_kind}"), value_style),
                 ]),
             ],
+            Self::Locked { pc } => vec![format!("CPU locked up at ${pc:04X}").into()],
             Self::ReadByte { address, value } => vec![format!("${address:04X} = {value}").into()],
             Self::ReadWord { address, value } => vec![format!("${address:04X} = {value}").into()],
             Self::WriteByte { address, value } => {
@@ -118,6 +119,30 @@ impl AsLogItem for CommandOutput {
             Self::WriteWord { address, value } => {
                 vec![format!("Set ${address:04X} = {value}").into()]
             }
+            Self::Rtc {
+                time,
+                halted,
+                carry,
+            } => {
+                let mut spans = vec![Span::styled(
+                    format!(
+                        "Day {}, {:02}:{:02}:{:02}",
+                        time.days, time.hours, time.minutes, time.seconds
+                    ),
+                    value_style,
+                )];
+
+                if *halted {
+                    spans.push(Span::raw(" (halted)"));
+                }
+
+                if *carry {
+                    spans.push(Span::raw(" (day carry)"));
+                }
+
+                vec![Spans::from(spans)]
+            }
+            Self::NoRtc => vec!["Cartridge has no real time clock".into()],
         }
     }
 }
@@ -68,7 +68,7 @@ fn draw_left_column<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
 fn draw_registers<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
     let area = draw_container(f, area, "Registers");
 
-    let registers = Registers::new(&app.hardware.cpu.registers);
+    let registers = Registers::new(&app.hardware.cpu.registers, app.hardware.cpu.state);
     f.render_widget(registers, area);
 }
 
@@ -1,5 +1,5 @@
 use gb_rs_asm::containers::Pair;
-use gb_rs_core::cpu::{self, registers::FlagsRegister};
+use gb_rs_core::cpu::{self, registers::FlagsRegister, State};
 use tui::{
     buffer::Buffer,
     layout::Rect,
@@ -22,11 +22,12 @@ macro_rules! draw_registers {
 
 pub struct Registers<'a> {
     registers: &'a cpu::registers::Registers,
+    state: State,
 }
 
 impl<'a> Registers<'a> {
-    pub fn new(registers: &'a cpu::registers::Registers) -> Self {
-        Self { registers }
+    pub fn new(registers: &'a cpu::registers::Registers, state: State) -> Self {
+        Self { registers, state }
     }
 }
 
@@ -64,6 +65,7 @@ impl<'a> Widget for Registers<'a> {
         draw_registers!(
             with buf, using column_width;
 
+            (left, top) "St" => self.state;
             (left, top + 1) "BC" => self.registers.get_pair(Pair::BC);
             (left, top + 2) "DE" => self.registers.get_pair(Pair::DE);
             (left, top + 3) "HL" => self.registers.get_pair(Pair::HL);
@@ -80,6 +82,13 @@ where
         RegisterKind::Word(n) => format!("{n:>5}"),
         RegisterKind::Address(n) => format!("${n:04X} ({n:>5})"),
         RegisterKind::Flags(flags) => format!("{flags}"),
+        RegisterKind::State(state) => match state {
+            State::Running => "running".to_owned(),
+            State::Halted => "halted".to_owned(),
+            State::Stopped => "stopped".to_owned(),
+            State::SwitchingSpeed(_) => "switching speed".to_owned(),
+            State::Locked { pc } => format!("locked (${pc:04X})"),
+        },
     };
 
     vec![
@@ -94,6 +103,7 @@ enum RegisterKind<'a> {
     Word(u16),
     Address(u16),
     Flags(&'a FlagsRegister),
+    State(State),
 }
 
 impl From<u8> for RegisterKind<'_> {
@@ -108,6 +118,12 @@ impl From<u16> for RegisterKind<'_> {
     }
 }
 
+impl From<State> for RegisterKind<'_> {
+    fn from(value: State) -> Self {
+        Self::State(value)
+    }
+}
+
 impl<'a> From<&'a FlagsRegister> for RegisterKind<'a> {
     fn from(value: &'a FlagsRegister) -> Self {
         Self::Flags(value)
@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+/// The number of T-cycles (ticks of the system oscillator) in one M-cycle (machine cycle).
+pub const T_CYCLES_PER_M_CYCLE: u64 = 4;
+
+/// The number of T-cycles per second when running at normal speed.
+pub const T_CYCLES_PER_SECOND: u64 = 4_194_304;
+
+/// The speed the CPU is running at. Only the CGB supports [`Speed::Double`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Speed {
+    #[default]
+    Normal,
+    Double,
+}
+
+impl Speed {
+    pub fn from_double_speed(double_speed: bool) -> Self {
+        if double_speed {
+            Self::Double
+        } else {
+            Self::Normal
+        }
+    }
+
+    /// Returns the number of T-cycles the CPU executes per second at this speed.
+    pub fn t_cycles_per_second(self) -> u64 {
+        match self {
+            Self::Normal => T_CYCLES_PER_SECOND,
+            Self::Double => T_CYCLES_PER_SECOND * 2,
+        }
+    }
+}
+
+/// A monotonic count of the cycles executed by the CPU.
+///
+/// All counts are in CPU cycles, so in double speed mode each cycle takes half as long in
+/// emulated time. Cycles are tracked separately per [`Speed`] so that [`Clock::elapsed()`] stays
+/// exact across speed switches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Clock {
+    normal_m_cycles: u64,
+    double_m_cycles: u64,
+}
+
+impl Clock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that `m_cycles` M-cycles have passed at the given speed.
+    pub fn advance(&mut self, m_cycles: u64, speed: Speed) {
+        match speed {
+            Speed::Normal => self.normal_m_cycles += m_cycles,
+            Speed::Double => self.double_m_cycles += m_cycles,
+        }
+    }
+
+    /// Returns the total number of M-cycles executed.
+    pub fn m_cycles(&self) -> u64 {
+        self.normal_m_cycles + self.double_m_cycles
+    }
+
+    /// Returns the total number of T-cycles executed.
+    pub fn t_cycles(&self) -> u64 {
+        self.m_cycles() * T_CYCLES_PER_M_CYCLE
+    }
+
+    /// Returns the amount of emulated time that has passed, taking double speed into account.
+    pub fn elapsed(&self) -> Duration {
+        // Expressed in normal speed T-cycles, a double speed M-cycle is only 2 T-cycles long.
+        let t_cycles = u128::from(self.normal_m_cycles) * u128::from(T_CYCLES_PER_M_CYCLE)
+            + u128::from(self.double_m_cycles) * u128::from(T_CYCLES_PER_M_CYCLE / 2);
+
+        let nanos = t_cycles * 1_000_000_000 / u128::from(T_CYCLES_PER_SECOND);
+
+        Duration::new(
+            (nanos / 1_000_000_000) as u64,
+            (nanos % 1_000_000_000) as u32,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elapsed_accounts_for_double_speed() {
+        let mut clock = Clock::new();
+
+        clock.advance(T_CYCLES_PER_SECOND / T_CYCLES_PER_M_CYCLE, Speed::Normal);
+        assert_eq!(clock.elapsed(), Duration::from_secs(1));
+
+        clock.advance(T_CYCLES_PER_SECOND / T_CYCLES_PER_M_CYCLE, Speed::Double);
+        assert_eq!(clock.elapsed(), Duration::from_millis(1500));
+
+        assert_eq!(clock.m_cycles(), T_CYCLES_PER_SECOND / 2);
+        assert_eq!(clock.t_cycles(), T_CYCLES_PER_SECOND * 2);
+    }
+}
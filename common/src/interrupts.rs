@@ -0,0 +1,40 @@
+/// The interrupt sources connected to the CPU, in order of priority (highest first).
+///
+/// Each source is assigned a bit in the `IE` and `IF` registers, starting with
+/// [`Interrupt::VBlank`] at bit 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interrupt {
+    VBlank,
+    LcdStat,
+    Timer,
+    Serial,
+    Joypad,
+}
+
+impl Interrupt {
+    pub const ALL: [Self; 5] = [
+        Self::VBlank,
+        Self::LcdStat,
+        Self::Timer,
+        Self::Serial,
+        Self::Joypad,
+    ];
+
+    /// Returns the mask for this interrupt's bit in the `IE` and `IF` registers.
+    pub fn mask(self) -> u8 {
+        1 << self as u8
+    }
+
+    /// Returns the address the CPU jumps to when servicing this interrupt.
+    pub fn vector(self) -> u16 {
+        0x40 + 8 * self as u16
+    }
+
+    /// Returns the highest priority interrupt set in `flags`, if any.
+    pub fn highest_priority(flags: u8) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|interrupt| flags & interrupt.mask() != 0)
+    }
+}
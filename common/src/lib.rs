@@ -1,6 +1,9 @@
 pub mod bytes;
+pub mod clock;
+pub mod interrupts;
+pub mod model;
 
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone)]
 pub enum DeviceMode {
     Color,
     Classic,
@@ -0,0 +1,120 @@
+use crate::DeviceMode;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// A specific Game Boy hardware revision.
+///
+/// Each model leaves the CPU and IO registers in a slightly different state after its boot ROM
+/// has run, which some games (and most test ROMs) use to detect which hardware they're on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Model {
+    /// The original Game Boy, with its early boot ROM revision.
+    Dmg0,
+    Dmg,
+
+    /// The Game Boy Pocket and Game Boy Light.
+    Mgb,
+    Sgb,
+    Sgb2,
+    Cgb,
+
+    /// The Game Boy Advance, running Game Boy software.
+    Agb,
+}
+
+impl Model {
+    pub const ALL: [Self; 7] = [
+        Self::Dmg0,
+        Self::Dmg,
+        Self::Mgb,
+        Self::Sgb,
+        Self::Sgb2,
+        Self::Cgb,
+        Self::Agb,
+    ];
+
+    /// Returns `true` if the model has Game Boy Color hardware.
+    pub fn is_color(self) -> bool {
+        matches!(self, Self::Cgb | Self::Agb)
+    }
+
+    /// Returns `true` if the model is a Super Game Boy.
+    pub fn is_super(self) -> bool {
+        matches!(self, Self::Sgb | Self::Sgb2)
+    }
+
+    /// Returns the mode the model runs a cartridge in.
+    ///
+    /// Color hardware falls back to [`DeviceMode::Classic`] (i.e. "DMG compatibility mode") for
+    /// cartridges that don't support the Game Boy Color.
+    pub fn device_mode(self, color_cartridge: bool) -> DeviceMode {
+        if self.is_color() && color_cartridge {
+            DeviceMode::Color
+        } else {
+            DeviceMode::Classic
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Dmg0 => "dmg0",
+            Self::Dmg => "dmg",
+            Self::Mgb => "mgb",
+            Self::Sgb => "sgb",
+            Self::Sgb2 => "sgb2",
+            Self::Cgb => "cgb",
+            Self::Agb => "agb",
+        }
+    }
+}
+
+impl Display for Model {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Model {
+    type Err = ParseModelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|model| model.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParseModelError(s.to_owned()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseModelError(String);
+
+impl Display for ParseModelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "unknown model {:?}, expected one of dmg0, dmg, mgb, sgb, sgb2, cgb or agb",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseModelError {}
+
+/// The parts of the cartridge header that the boot ROM inspects, and which affect the state it
+/// leaves the CPU in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BootHeader {
+    /// The header checksum at `$014D`.
+    pub header_checksum: u8,
+
+    /// The sum of the 16 title bytes at `$0134-$0143`.
+    pub title_checksum: u8,
+
+    /// `true` if the licensee is Nintendo, in which case color hardware uses the title checksum
+    /// to pick a palette for Classic cartridges.
+    pub nintendo_licensee: bool,
+
+    /// `true` if the cartridge supports the Game Boy Color.
+    pub color: bool,
+}
@@ -0,0 +1,200 @@
+use crate::Error;
+use gb_rs_common::clock::{Clock, T_CYCLES_PER_M_CYCLE, T_CYCLES_PER_SECOND};
+use gb_rs_memory::cartridge::Cartridge;
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// The default amount of emulated time between checks for changes to battery-backed RAM.
+pub const DEFAULT_SAVE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Keeps a cartridge's battery-backed RAM in sync with a `.sav` file.
+#[derive(Debug)]
+pub(crate) struct BatterySave {
+    path: PathBuf,
+
+    /// The interval between checks, in M-cycles. In double speed mode, this means checks happen
+    /// twice as often in emulated time.
+    interval: u64,
+
+    /// The M-cycle count at which to next check for changes.
+    next_check: u64,
+
+    /// The cartridge's [`ram_snapshot`](Cartridge::ram_snapshot) as of the last time the save
+    /// file was read or written.
+    saved: Vec<u8>,
+}
+
+impl BatterySave {
+    /// Loads the save file at `path` into the cartridge's RAM, if it exists.
+    pub fn load(
+        path: PathBuf,
+        interval: Duration,
+        cartridge: &mut Cartridge,
+    ) -> Result<Self, Error> {
+        match fs::read(&path) {
+            Ok(data) => cartridge.import_ram(&data)?,
+            // A new save file will be created once the game writes something.
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (),
+            Err(e) => return Err(e.into()),
+        };
+
+        let interval = (interval.as_nanos() * u128::from(T_CYCLES_PER_SECOND)
+            / u128::from(T_CYCLES_PER_M_CYCLE)
+            / 1_000_000_000) as u64;
+
+        Ok(Self {
+            path,
+            interval,
+            next_check: interval,
+            saved: cartridge.ram_snapshot(),
+        })
+    }
+
+    /// Writes the save file if it's time to check for changes, and the RAM has changed.
+    ///
+    /// This is called after every instruction, so it only compares cycle counts until it's time to
+    /// check.
+    pub fn tick(&mut self, cartridge: &Cartridge, clock: &Clock) -> io::Result<()> {
+        let m_cycles = clock.m_cycles();
+
+        if m_cycles < self.next_check {
+            return Ok(());
+        }
+
+        self.next_check = m_cycles + self.interval;
+        self.flush(cartridge)
+    }
+
+    /// Writes the save file if the RAM has changed since it was last written. An RTC counting
+    /// time doesn't count as a change, but its current state is saved along with everything else.
+    pub fn flush(&mut self, cartridge: &Cartridge) -> io::Result<()> {
+        let snapshot = cartridge.ram_snapshot();
+
+        if snapshot != self.saved {
+            write_atomically(&self.path, &cartridge.export_ram())?;
+            self.saved = snapshot;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes `data` to a temporary file next to `path`, then renames it over `path`. This way, the
+/// save file is never left half-written if the process is killed mid-write.
+fn write_atomically(path: &Path, data: &[u8]) -> io::Result<()> {
+    let mut temp_path = OsString::from(path);
+    temp_path.push(".tmp");
+
+    let mut file = File::create(&temp_path)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+
+    fs::rename(&temp_path, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Hardware;
+    use gb_rs_common::clock::{Speed, T_CYCLES_PER_M_CYCLE, T_CYCLES_PER_SECOND};
+    use gb_rs_memory::cartridge::constants::{CONTROLLER_TYPE, RAM_SIZE};
+    use gb_rs_memory::cartridge::mbc::mbc3::rtc::FOOTER_SIZE;
+    use std::fs;
+
+    #[test]
+    fn saves_and_loads_ram() {
+        let mut rom = vec![0; 0x8000];
+        rom[CONTROLLER_TYPE] = 0x03; // MBC1+RAM+BATTERY
+        rom[RAM_SIZE] = 0x02; // 8 KiB
+
+        let path = std::env::temp_dir().join(format!("gb-rs-battery-{}.sav", std::process::id()));
+        let build = || {
+            Hardware::builder()
+                .save_file(&path)
+                .build(rom.clone())
+                .unwrap()
+        };
+
+        let mut hardware = build();
+
+        // Nothing is written until the RAM changes.
+        hardware.save().unwrap();
+        assert!(!path.exists());
+
+        hardware.memory.write_byte(0x0000, 0x0A);
+        hardware.memory.write_byte(0xA123, 0x45);
+        hardware.save().unwrap();
+
+        let saved = fs::read(&path).unwrap();
+        assert_eq!(saved.len(), 0x2000);
+        assert_eq!(saved[0x123], 0x45);
+
+        let mut hardware = build();
+        hardware.memory.write_byte(0x0000, 0x0A);
+        assert_eq!(hardware.memory.read_byte(0xA123), 0x45);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn rtc_counting_is_not_a_change() {
+        let mut rom = vec![0; 0x8000];
+        rom[CONTROLLER_TYPE] = 0x10; // MBC3+TIMER+RAM+BATTERY
+        rom[RAM_SIZE] = 0x02; // 8 KiB
+
+        let path = std::env::temp_dir().join(format!("gb-rs-rtc-{}.sav", std::process::id()));
+        let mut hardware = Hardware::builder().save_file(&path).build(rom).unwrap();
+
+        let rtc = hardware.memory.cartridge.rtc_mut().unwrap();
+        rtc.tick(
+            T_CYCLES_PER_SECOND * 5 / T_CYCLES_PER_M_CYCLE,
+            Speed::Normal,
+        );
+        assert_eq!(rtc.get_time_parts()[0], 5);
+
+        hardware.save().unwrap();
+        assert!(!path.exists());
+
+        // The game stopping the clock is worth saving, though.
+        hardware.memory.write_byte(0x0000, 0x0A);
+        hardware.memory.write_byte(0x4000, 0x0C); // Days high
+        hardware.memory.write_byte(0xA000, 0x40);
+        hardware.save().unwrap();
+        assert_eq!(fs::read(&path).unwrap().len(), 0x2000 + FOOTER_SIZE);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn no_save_ignores_save_file() {
+        let mut rom = vec![0; 0x8000];
+        rom[CONTROLLER_TYPE] = 0x03; // MBC1+RAM+BATTERY
+        rom[RAM_SIZE] = 0x02; // 8 KiB
+
+        let dir = std::env::temp_dir();
+        let rom_path = dir.join(format!("gb-rs-no-save-{}.gb", std::process::id()));
+        let save_path = rom_path.with_extension("sav");
+        fs::write(&rom_path, &rom).unwrap();
+        fs::write(&save_path, [0x45; 0x2000]).unwrap();
+
+        let mut hardware = Hardware::builder()
+            .no_save()
+            .build_from_file(&rom_path)
+            .unwrap();
+
+        hardware.memory.write_byte(0x0000, 0x0A);
+        assert_eq!(hardware.memory.read_byte(0xA000), 0x00);
+
+        hardware.memory.write_byte(0xA000, 0x12);
+        hardware.save().unwrap();
+        drop(hardware);
+        assert_eq!(fs::read(&save_path).unwrap(), [0x45; 0x2000]);
+
+        fs::remove_file(&rom_path).unwrap();
+        fs::remove_file(&save_path).unwrap();
+    }
+}
@@ -0,0 +1,65 @@
+//! Runs a set of test ROMs headlessly and prints a summary table.
+//!
+//! Usage: `test_roms [--budget <m-cycles>] <rom or directory>...`
+//!
+//! Exits with a non-zero status if any ROM didn't pass.
+
+use gb_rs_core::test_rom::{self, DEFAULT_CYCLE_BUDGET};
+use std::{env, path::PathBuf, process::ExitCode};
+
+fn main() -> ExitCode {
+    let mut budget = DEFAULT_CYCLE_BUDGET;
+    let mut paths = Vec::new();
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        if arg == "--budget" {
+            budget = match args.next().map(|value| value.parse()) {
+                Some(Ok(value)) => value,
+                _ => return usage(),
+            };
+        } else {
+            paths.push(PathBuf::from(arg));
+        }
+    }
+
+    if paths.is_empty() {
+        return usage();
+    }
+
+    let mut all_passed = true;
+
+    for path in paths {
+        let results = if path.is_dir() {
+            match test_rom::run_dir(&path, budget) {
+                Ok(results) => results,
+                Err(e) => {
+                    eprintln!("could not read {}: {}", path.display(), e);
+                    return ExitCode::FAILURE;
+                }
+            }
+        } else {
+            vec![test_rom::run(&path, budget)]
+        };
+
+        let base = if path.is_dir() {
+            path.as_path()
+        } else {
+            path.parent().unwrap_or(&path)
+        };
+
+        print!("{}", test_rom::summary(&results, base));
+        all_passed &= results.iter().all(|result| result.verdict.is_pass());
+    }
+
+    if all_passed {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn usage() -> ExitCode {
+    eprintln!("usage: test_roms [--budget <m-cycles>] <rom or directory>...");
+    ExitCode::FAILURE
+}
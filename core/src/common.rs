@@ -0,0 +1 @@
+pub use gb_rs_common::*;
@@ -1,32 +1,241 @@
-use gb_rs_cpu::Cpu;
-use gb_rs_memory::Memory;
-use std::{fs::File, io::Read, path::Path};
+use crate::battery::{BatterySave, DEFAULT_SAVE_INTERVAL};
+use gb_rs_common::{clock::Clock, model::Model};
+use gb_rs_cpu::{Cpu, CpuError, StepOutcome};
+use gb_rs_memory::{
+    cartridge::{
+        get_device_mode,
+        mbc::{mbc3::clock::RtcClock, ImportError},
+        SupportedDeviceMode,
+    },
+    constants::{CGB_BOOT_ROM_SIZE, DMG_BOOT_ROM_SIZE},
+    joypad::Buttons,
+    serial::{Disconnected, LinkCable},
+    Memory,
+};
+use std::{
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 pub struct Hardware {
     pub cpu: Cpu,
     pub memory: Memory,
+    battery: Option<BatterySave>,
 }
 
 impl Hardware {
+    pub fn builder() -> HardwareBuilder {
+        HardwareBuilder::default()
+    }
+
     pub fn from_file(cart_file: &Path) -> Result<Self, Error> {
-        let mut file = File::open(cart_file)?;
-        let len = file.metadata()?.len();
-        let len: usize = len.try_into().map_err(|_| Error::FileTooBig)?;
+        Self::builder().build_from_file(cart_file)
+    }
+
+    pub fn from_rom(rom: Vec<u8>) -> Result<Self, Error> {
+        Self::builder().build(rom)
+    }
+
+    pub fn model(&self) -> Model {
+        self.memory.get_model()
+    }
+
+    pub fn step(&mut self) -> Result<StepOutcome, Error> {
+        let outcome = self.cpu.step(&mut self.memory)?;
+
+        if let Some(battery) = &mut self.battery {
+            battery.tick(&self.memory.cartridge, &self.cpu.clock)?;
+        }
+
+        Ok(outcome)
+    }
+
+    /// Writes the cartridge's battery-backed RAM to its save file, if it has changed since it was
+    /// last saved. This also happens periodically while running, and when the hardware is
+    /// dropped.
+    pub fn save(&mut self) -> Result<(), Error> {
+        if let Some(battery) = &mut self.battery {
+            battery.flush(&self.memory.cartridge)?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the buttons that are currently held down, releasing any others.
+    pub fn set_buttons(&mut self, buttons: Buttons) {
+        self.memory.set_buttons(buttons);
+    }
+
+    /// Presses `buttons`, leaving any others that are held down as they are.
+    pub fn press(&mut self, buttons: Buttons) {
+        self.memory.set_buttons(self.memory.buttons() | buttons);
+    }
+
+    /// Releases `buttons`, leaving any others that are held down as they are.
+    pub fn release(&mut self, buttons: Buttons) {
+        self.memory.set_buttons(self.memory.buttons() - buttons);
+    }
+
+    /// Returns the state of the cartridge's rumble motor (`true` if it's on) if it has been
+    /// switched on or off since the last call. Frontends can call this once per frame to drive a
+    /// controller's vibration.
+    pub fn poll_rumble(&mut self) -> Option<bool> {
+        self.memory.cartridge.poll_rumble()
+    }
 
-        let mut rom: Vec<u8> = Vec::with_capacity(len);
-        file.read_to_end(&mut rom)?;
+    /// Plugs `cable` into the link port, replacing whatever was plugged in before.
+    pub fn connect_link_cable(&mut self, cable: impl LinkCable + 'static) {
+        self.memory.connect_link_cable(Box::new(cable));
+    }
 
-        let memory = Memory::new(rom)?;
+    /// Unplugs the link cable, returning it so it can be plugged into something else.
+    pub fn disconnect_link_cable(&mut self) -> Box<dyn LinkCable> {
+        self.memory.connect_link_cable(Box::new(Disconnected))
+    }
+
+    /// Returns the clock tracking the total number of cycles executed since power on.
+    pub fn clock(&self) -> &Clock {
+        &self.cpu.clock
+    }
+}
+
+impl Drop for Hardware {
+    fn drop(&mut self) {
+        // There's nowhere to report an error to here, so call `save` beforehand to handle them.
+        let _ = self.save();
+    }
+}
 
-        Ok(Self {
-            cpu: Cpu::new(memory.cartridge.device_mode.into()),
+/// Configures the hardware to emulate, before loading a cartridge into it.
+#[derive(Debug, Default, Clone)]
+pub struct HardwareBuilder {
+    model: Option<Model>,
+    boot_rom: Option<Vec<u8>>,
+    save_file: Option<PathBuf>,
+    no_save: bool,
+    save_interval: Option<Duration>,
+    rtc_clock: RtcClock,
+}
+
+impl HardwareBuilder {
+    /// Sets the model to emulate.
+    ///
+    /// If not set, the model is picked based on the size of the boot ROM, if there is one.
+    /// Otherwise, it's picked based on the cartridge: [`Model::Cgb`] for cartridges that support
+    /// the Game Boy Color, and [`Model::Dmg`] otherwise.
+    pub fn model(mut self, model: Model) -> Self {
+        self.model = Some(model);
+        self
+    }
+
+    /// Runs `boot_rom` on power on, instead of starting the cartridge at `$0100` with the state
+    /// the boot ROM would have left behind.
+    pub fn boot_rom(mut self, boot_rom: Vec<u8>) -> Self {
+        self.boot_rom = Some(boot_rom);
+        self
+    }
+
+    pub fn boot_rom_file(self, boot_rom_file: &Path) -> Result<Self, Error> {
+        Ok(self.boot_rom(read_file(boot_rom_file)?))
+    }
+
+    /// Sets the file that battery-backed cartridge RAM is loaded from and saved to. This is
+    /// ignored for cartridges without a battery.
+    ///
+    /// If not set, [`build_from_file`](Self::build_from_file) uses the cartridge's path with a
+    /// `.sav` extension, and [`build`](Self::build) doesn't save at all.
+    pub fn save_file(mut self, save_file: impl Into<PathBuf>) -> Self {
+        self.save_file = Some(save_file.into());
+        self.no_save = false;
+        self
+    }
+
+    /// Stops battery-backed RAM from being loaded from or saved to a file, even by
+    /// [`build_from_file`](Self::build_from_file), so every run starts from blank RAM.
+    pub fn no_save(mut self) -> Self {
+        self.save_file = None;
+        self.no_save = true;
+        self
+    }
+
+    /// Sets how much emulated time passes between checks for changes to battery-backed RAM,
+    /// which are written to the save file. Defaults to [`DEFAULT_SAVE_INTERVAL`].
+    ///
+    /// The interval is counted in CPU cycles at normal speed, so checks happen twice as often in
+    /// double speed mode.
+    pub fn save_interval(mut self, save_interval: Duration) -> Self {
+        self.save_interval = Some(save_interval);
+        self
+    }
+
+    /// Sets what drives the cartridge's real time clock, if it has one. Defaults to
+    /// [`RtcClock::Emulated`].
+    pub fn rtc_clock(mut self, rtc_clock: RtcClock) -> Self {
+        self.rtc_clock = rtc_clock;
+        self
+    }
+
+    pub fn build_from_file(mut self, cart_file: &Path) -> Result<Hardware, Error> {
+        if self.save_file.is_none() && !self.no_save {
+            self.save_file = Some(cart_file.with_extension("sav"));
+        }
+
+        self.build(read_file(cart_file)?)
+    }
+
+    pub fn build(self, rom: Vec<u8>) -> Result<Hardware, Error> {
+        let model = self.model.unwrap_or_else(|| match &self.boot_rom {
+            Some(boot_rom) if boot_rom.len() == DMG_BOOT_ROM_SIZE => Model::Dmg,
+            Some(boot_rom) if boot_rom.len() == CGB_BOOT_ROM_SIZE => Model::Cgb,
+            _ => match get_device_mode(&rom) {
+                SupportedDeviceMode::Classic => Model::Dmg,
+                SupportedDeviceMode::Color | SupportedDeviceMode::Any => Model::Cgb,
+            },
+        });
+
+        let (cpu, mut memory) = match self.boot_rom {
+            Some(boot_rom) => (
+                Cpu::power_on(),
+                Memory::with_boot_rom(rom, model, boot_rom)?,
+            ),
+            None => {
+                let memory = Memory::new(rom, model)?;
+                (Cpu::new(model, &memory.cartridge.boot_header()), memory)
+            }
+        };
+
+        if let Some(rtc) = memory.cartridge.rtc_mut() {
+            rtc.set_clock(self.rtc_clock.create());
+        }
+
+        let battery = match self.save_file {
+            Some(save_file) if memory.cartridge.has_battery() => Some(BatterySave::load(
+                save_file,
+                self.save_interval.unwrap_or(DEFAULT_SAVE_INTERVAL),
+                &mut memory.cartridge,
+            )?),
+            _ => None,
+        };
+
+        Ok(Hardware {
+            cpu,
             memory,
+            battery,
         })
     }
+}
 
-    pub fn step(&mut self) {
-        self.cpu.step(&mut self.memory);
-    }
+fn read_file(path: &Path) -> Result<Vec<u8>, Error> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let len: usize = len.try_into().map_err(|_| Error::FileTooBig)?;
+
+    let mut contents: Vec<u8> = Vec::with_capacity(len);
+    file.read_to_end(&mut contents)?;
+
+    Ok(contents)
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -37,6 +246,12 @@ pub enum Error {
     #[error("memory error: {0}")]
     Memory(#[from] gb_rs_memory::MemoryError),
 
+    #[error("cpu error: {0}")]
+    Cpu(#[from] CpuError),
+
+    #[error("save file error: {0}")]
+    Save(#[from] ImportError),
+
     #[error("cart file size too big")]
     FileTooBig,
 }
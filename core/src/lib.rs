@@ -1,6 +1,10 @@
+pub use battery::DEFAULT_SAVE_INTERVAL;
 pub use hardware::*;
 
+pub mod common;
 pub mod cpu;
 pub mod memory;
+pub mod test_rom;
 
+mod battery;
 mod hardware;
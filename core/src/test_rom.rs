@@ -0,0 +1,252 @@
+//! Headless runner for the Blargg and Mooneye test ROM suites.
+//!
+//! Blargg's ROMs print their results over the link port, ending with either "Passed" or "Failed".
+//! Mooneye's ROMs execute `LD B, B` once finished, with the Fibonacci sequence 3, 5, 8, 13, 21, 34
+//! loaded into B, C, D, E, H and L on success, or `$42` in every register on failure.
+
+use crate::{Error, Hardware};
+use gb_rs_common::clock::{T_CYCLES_PER_M_CYCLE, T_CYCLES_PER_SECOND};
+use gb_rs_cpu::{registers::Registers, StepOutcome};
+use gb_rs_memory::serial::Capture;
+use std::{
+    fmt::{Display, Write},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The default number of M-cycles a ROM may run for before it's considered to have timed out.
+/// This is two minutes of emulated time, which is enough for even the slowest of Blargg's ROMs.
+pub const DEFAULT_CYCLE_BUDGET: u64 = 120 * T_CYCLES_PER_SECOND / T_CYCLES_PER_M_CYCLE;
+
+/// The opcode for `LD B, B`, which Mooneye's ROMs use as a software breakpoint.
+const LD_B_B: u8 = 0x40;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Passed,
+    Failed,
+
+    /// The ROM didn't report a result within its cycle budget.
+    TimedOut,
+
+    /// The CPU locked up after executing an illegal opcode at `pc`.
+    LockedUp {
+        pc: u16,
+    },
+
+    /// The ROM couldn't be loaded or executed.
+    Error(String),
+}
+
+impl Verdict {
+    pub fn is_pass(&self) -> bool {
+        *self == Self::Passed
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Passed => write!(f, "passed"),
+            Self::Failed => write!(f, "FAILED"),
+            Self::TimedOut => write!(f, "TIMED OUT"),
+            Self::LockedUp { pc } => write!(f, "LOCKED UP at ${:04X}", pc),
+            Self::Error(e) => write!(f, "ERROR: {}", e),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct TestRomResult {
+    pub path: PathBuf,
+    pub verdict: Verdict,
+
+    /// Everything the ROM printed over the link port.
+    pub serial: String,
+
+    /// The number of M-cycles the ROM ran for.
+    pub cycles: u64,
+}
+
+/// Runs a single test ROM until it reports a result, or until `budget` M-cycles have passed.
+pub fn run(path: &Path, budget: u64) -> TestRomResult {
+    let serial = Capture::default();
+
+    // Test ROMs shouldn't pick up or leave behind save files.
+    let (verdict, cycles) = match Hardware::builder().no_save().build_from_file(path) {
+        Ok(mut hardware) => {
+            let verdict = run_hardware(&mut hardware, budget, &serial)
+                .unwrap_or_else(|e| Verdict::Error(e.to_string()));
+
+            (verdict, hardware.clock().m_cycles())
+        }
+        Err(e) => (Verdict::Error(e.to_string()), 0),
+    };
+
+    TestRomResult {
+        path: path.to_owned(),
+        verdict,
+        serial: String::from_utf8_lossy(&serial.output()).into_owned(),
+        cycles,
+    }
+}
+
+/// Runs every `.gb` and `.gbc` file in `dir` (including subdirectories), in alphabetical order.
+pub fn run_dir(dir: &Path, budget: u64) -> io::Result<Vec<TestRomResult>> {
+    let mut roms = Vec::new();
+    find_roms(dir, &mut roms)?;
+    roms.sort();
+
+    Ok(roms.iter().map(|rom| run(rom, budget)).collect())
+}
+
+/// Formats a table of results, with paths shown relative to `base`.
+pub fn summary(results: &[TestRomResult], base: &Path) -> String {
+    let names: Vec<_> = results
+        .iter()
+        .map(|result| {
+            let path = result.path.strip_prefix(base).unwrap_or(&result.path);
+            path.display().to_string()
+        })
+        .collect();
+
+    let width = names.iter().map(String::len).max().unwrap_or(0).max(3);
+    let mut output = String::new();
+
+    writeln!(output, "{:<width$}  {:>12}  Result", "ROM", "Cycles").unwrap();
+
+    for (name, result) in names.iter().zip(results) {
+        writeln!(
+            output,
+            "{:<width$}  {:>12}  {}",
+            name, result.cycles, result.verdict
+        )
+        .unwrap();
+    }
+
+    let passed = results.iter().filter(|r| r.verdict.is_pass()).count();
+    writeln!(output, "\n{}/{} passed", passed, results.len()).unwrap();
+
+    output
+}
+
+fn find_roms(dir: &Path, roms: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            find_roms(&path, roms)?;
+        } else if matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some("gb" | "gbc")
+        ) {
+            roms.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+fn run_hardware(hardware: &mut Hardware, budget: u64, serial: &Capture) -> Result<Verdict, Error> {
+    hardware.connect_link_cable(serial.clone());
+
+    let mut sent = serial.len();
+
+    while hardware.clock().m_cycles() < budget {
+        let pc = hardware.cpu.registers.program_counter;
+        let breakpoint = hardware.memory.read_byte(pc) == LD_B_B;
+
+        match hardware.step()? {
+            StepOutcome::Locked { pc } => return Ok(Verdict::LockedUp { pc }),
+            StepOutcome::Executed if breakpoint => {
+                if let Some(verdict) = mooneye_verdict(&hardware.cpu.registers) {
+                    return Ok(verdict);
+                }
+            }
+            _ => (),
+        };
+
+        // Only check the output when something new was sent, since it needs to be copied.
+        if serial.len() != sent {
+            sent = serial.len();
+
+            if let Some(verdict) = blargg_verdict(&serial.output()) {
+                return Ok(verdict);
+            }
+        }
+    }
+
+    Ok(Verdict::TimedOut)
+}
+
+fn mooneye_verdict(registers: &Registers) -> Option<Verdict> {
+    let Registers {
+        b, c, d, e, h, l, ..
+    } = *registers;
+
+    match [b, c, d, e, h, l] {
+        [3, 5, 8, 13, 21, 34] => Some(Verdict::Passed),
+        [0x42, 0x42, 0x42, 0x42, 0x42, 0x42] => Some(Verdict::Failed),
+        _ => None,
+    }
+}
+
+fn blargg_verdict(serial: &[u8]) -> Option<Verdict> {
+    let contains = |needle: &[u8]| serial.windows(needle.len()).any(|w| w == needle);
+
+    if contains(b"Passed") {
+        Some(Verdict::Passed)
+    } else if contains(b"Failed") {
+        Some(Verdict::Failed)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a 32 KiB ROM-only cartridge with `program` at the entry point.
+    fn rom(program: &[u8]) -> Vec<u8> {
+        let mut rom = vec![0; 0x8000];
+        rom[0x100..0x100 + program.len()].copy_from_slice(program);
+
+        rom
+    }
+
+    #[test]
+    fn detects_mooneye_pass() {
+        #[rustfmt::skip]
+        let program = [
+            0x06, 3, 0x0E, 5, 0x16, 8, 0x1E, 13, 0x26, 21, 0x2E, 34, // LD r, n
+            0x40, // LD B, B
+        ];
+
+        let mut hardware = Hardware::from_rom(rom(&program)).unwrap();
+        let verdict = run_hardware(&mut hardware, 100, &Capture::default()).unwrap();
+
+        assert_eq!(verdict, Verdict::Passed);
+    }
+
+    #[test]
+    fn captures_serial_output() {
+        // Sends "Failed" over the link port, then loops forever.
+        let mut program = Vec::new();
+
+        for &byte in b"Failed" {
+            // LD A, byte; LDH (SB), A; LD A, $81; LDH (SC), A
+            program.extend_from_slice(&[0x3E, byte, 0xE0, 0x01, 0x3E, 0x81, 0xE0, 0x02]);
+        }
+
+        // JR -2
+        program.extend_from_slice(&[0x18, 0xFE]);
+
+        let mut hardware = Hardware::from_rom(rom(&program)).unwrap();
+        let serial = Capture::default();
+        let verdict = run_hardware(&mut hardware, 1000, &serial).unwrap();
+
+        assert_eq!(verdict, Verdict::Failed);
+        assert_eq!(serial.output(), b"Failed");
+    }
+}
@@ -0,0 +1,110 @@
+use gb_rs_asm::read::{self, Read};
+use gb_rs_common::bytes::{bytes_to_word, word_to_bytes};
+use gb_rs_common::clock::Speed;
+use gb_rs_common::interrupts::Interrupt;
+use gb_rs_memory::constants::{INTERRUPT_ENABLE, INTERRUPT_FLAGS};
+use gb_rs_memory::Memory;
+
+/// The CPU's view of the rest of the system.
+///
+/// [`Memory`] is the "real" implementation, but anything that can be read from and written to
+/// can be used to drive the CPU, e.g. a flat 64 KiB RAM for testing, or a wrapper that records
+/// each access.
+pub trait Bus {
+    fn read_byte(&self, address: u16) -> u8;
+
+    fn write_byte(&mut self, address: u16, value: u8);
+
+    fn read_word(&self, address: u16) -> u16 {
+        let low = self.read_byte(address);
+        let high = self.read_byte(address.wrapping_add(1));
+
+        bytes_to_word(high, low)
+    }
+
+    fn write_word(&mut self, address: u16, value: u16) {
+        let [high, low] = word_to_bytes(value);
+
+        self.write_byte(address, low);
+        self.write_byte(address.wrapping_add(1), high);
+    }
+
+    /// Advances the rest of the system (timers, DMA, etc.) by `cycles` M-cycles.
+    ///
+    /// The CPU calls this as it accesses the bus, so that other components observe the CPU's
+    /// reads and writes at the correct point in time.
+    fn tick(&mut self, _cycles: u8) {}
+
+    /// Returns the interrupts that are both requested (`IF`) and enabled (`IE`).
+    fn pending_interrupts(&self) -> u8 {
+        let flags = self.read_byte(INTERRUPT_FLAGS as u16);
+        let enabled = self.read_byte(INTERRUPT_ENABLE as u16);
+
+        flags & enabled & 0x1F
+    }
+
+    /// Clears an interrupt's bit in `IF`, once the CPU begins servicing it.
+    fn acknowledge_interrupt(&mut self, interrupt: Interrupt) {
+        let flags = self.read_byte(INTERRUPT_FLAGS as u16);
+        self.write_byte(INTERRUPT_FLAGS as u16, flags & !interrupt.mask());
+    }
+
+    /// Performs a CGB speed switch, if one is armed. Returns `true` if the speed was switched.
+    fn switch_speed(&mut self) -> bool {
+        false
+    }
+
+    /// Returns the speed the CPU is currently running at.
+    fn speed(&self) -> Speed {
+        Speed::Normal
+    }
+
+    /// Returns the number of cycles the CPU needs to pause for while another component (e.g.
+    /// VRAM DMA) has control of the bus, and resets it to zero.
+    fn take_stall_cycles(&mut self) -> u16 {
+        0
+    }
+}
+
+impl Bus for Memory {
+    fn read_byte(&self, address: u16) -> u8 {
+        Memory::read_byte(self, address)
+    }
+
+    fn write_byte(&mut self, address: u16, value: u8) {
+        Memory::write_byte(self, address, value);
+    }
+
+    fn tick(&mut self, cycles: u8) {
+        Memory::tick(self, cycles);
+    }
+
+    fn pending_interrupts(&self) -> u8 {
+        Memory::pending_interrupts(self)
+    }
+
+    fn acknowledge_interrupt(&mut self, interrupt: Interrupt) {
+        Memory::acknowledge_interrupt(self, interrupt);
+    }
+
+    fn switch_speed(&mut self) -> bool {
+        Memory::switch_speed(self)
+    }
+
+    fn speed(&self) -> Speed {
+        Speed::from_double_speed(self.is_double_speed())
+    }
+
+    fn take_stall_cycles(&mut self) -> u16 {
+        Memory::take_stall_cycles(self)
+    }
+}
+
+/// Adapts a [`Bus`] for use as a [`Read`] source, so instructions can be parsed from it.
+pub struct BusRead<'a, B>(pub &'a B);
+
+impl<B: Bus> Read for BusRead<'_, B> {
+    fn read_byte(&self, offset: u16) -> read::Result<u8> {
+        Ok(self.0.read_byte(offset))
+    }
+}
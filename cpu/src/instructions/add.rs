@@ -1,26 +1,32 @@
 use super::{Effect, Execute};
-use crate::{enum_pass_execute, Cpu};
+use crate::{bus::Bus, enum_pass_execute, Cpu};
 use gb_rs_asm::{
     containers::{Cycles, Flag, Pair},
     operations::add::*,
 };
 use gb_rs_common::Z80Add;
-use gb_rs_memory::Memory;
 
 impl Execute for Add {
     enum_pass_execute!(Self::Pair(inner), Self::Register(inner));
 }
 
 impl Execute for PairAdd {
-    fn execute(self, cpu: &mut Cpu, _memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         let lhs = cpu.registers.get_pair(self.target);
 
         let output = match self.source {
             PairAddSource::Pair(source) => {
+                cpu.idle(bus);
+
                 let rhs = cpu.registers.get_pair(source);
                 lhs.add_with_flags(rhs)
             }
-            PairAddSource::SignedData(data) => lhs.add_with_flags(data.as_twos_complement()),
+            PairAddSource::SignedData(data) => {
+                cpu.idle(bus);
+                cpu.idle(bus);
+
+                lhs.add_with_flags(data.as_twos_complement())
+            }
         };
 
         cpu.registers.set_pair(self.target, output.result);
@@ -37,14 +43,14 @@ impl Execute for PairAdd {
 }
 
 impl Execute for RegisterAdd {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         use RegisterAddSource::*;
 
         let rhs = match self.source {
             Register(reg) => cpu.registers.get_byte(reg),
             PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer);
-                memory.read_byte(addr)
+                cpu.read(bus, addr)
             }
             Data(data) => *data,
         };
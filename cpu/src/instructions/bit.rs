@@ -1,5 +1,5 @@
 use super::{Effect, Execute};
-use crate::Cpu;
+use crate::{bus::Bus, Cpu};
 use gb_rs_asm::{
     containers::{Cycles, Flag},
     instructions::bit::{
@@ -11,10 +11,9 @@ use gb_rs_asm::{
         Bit, BitwiseAnd, BitwiseAndTarget, BitwiseOr, BitwiseOrTarget, BitwiseXor, BitwiseXorTarget,
     },
 };
-use gb_rs_memory::Memory;
 
 impl Execute for Bit {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         match self {
             Self::SetCarryFlag => {
                 cpu.registers.flags.set(Flag::Carry);
@@ -23,22 +22,22 @@ impl Execute for Bit {
 
                 Effect { cycles: 1 }
             }
-            Self::Complement(inner) => inner.execute(cpu, memory, cycles),
-            Self::Set(inner) => inner.execute(cpu, memory, cycles),
-            Self::Reset(inner) => inner.execute(cpu, memory, cycles),
-            Self::Test(inner) => inner.execute(cpu, memory, cycles),
-            Self::And(inner) => inner.execute(cpu, memory, cycles),
-            Self::Xor(inner) => inner.execute(cpu, memory, cycles),
-            Self::Or(inner) => inner.execute(cpu, memory, cycles),
-            Self::ShiftLeft(inner) => inner.execute(cpu, memory, cycles),
-            Self::ShiftRight(inner) => inner.execute(cpu, memory, cycles),
-            Self::Swap(inner) => inner.execute(cpu, memory, cycles),
+            Self::Complement(inner) => inner.execute(cpu, bus, cycles),
+            Self::Set(inner) => inner.execute(cpu, bus, cycles),
+            Self::Reset(inner) => inner.execute(cpu, bus, cycles),
+            Self::Test(inner) => inner.execute(cpu, bus, cycles),
+            Self::And(inner) => inner.execute(cpu, bus, cycles),
+            Self::Xor(inner) => inner.execute(cpu, bus, cycles),
+            Self::Or(inner) => inner.execute(cpu, bus, cycles),
+            Self::ShiftLeft(inner) => inner.execute(cpu, bus, cycles),
+            Self::ShiftRight(inner) => inner.execute(cpu, bus, cycles),
+            Self::Swap(inner) => inner.execute(cpu, bus, cycles),
         }
     }
 }
 
 impl Execute for Complement {
-    fn execute(self, cpu: &mut Cpu, _memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, _bus: &mut B, cycles: Cycles) -> Effect {
         use ComplementTarget::*;
 
         match self.target {
@@ -61,7 +60,7 @@ impl Execute for Complement {
 }
 
 impl Execute for BitwiseSet {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         match self.target {
             BitwiseSetTarget::Register(reg) => {
                 let value = cpu.registers.get_byte(reg);
@@ -70,8 +69,8 @@ impl Execute for BitwiseSet {
             BitwiseSetTarget::PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer);
 
-                let value = memory.read_byte(addr);
-                memory.write_byte(addr, self.bit.with_set(value));
+                let value = cpu.read(bus, addr);
+                cpu.write(bus, addr, self.bit.with_set(value));
             }
         };
 
@@ -80,7 +79,7 @@ impl Execute for BitwiseSet {
 }
 
 impl Execute for BitwiseReset {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         match self.target {
             BitwiseResetTarget::Register(reg) => {
                 let value = cpu.registers.get_byte(reg);
@@ -89,8 +88,8 @@ impl Execute for BitwiseReset {
             BitwiseResetTarget::PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer);
 
-                let value = memory.read_byte(addr);
-                memory.write_byte(addr, self.bit.with_unset(value));
+                let value = cpu.read(bus, addr);
+                cpu.write(bus, addr, self.bit.with_unset(value));
             }
         };
 
@@ -99,7 +98,7 @@ impl Execute for BitwiseReset {
 }
 
 impl Execute for BitwiseTest {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         let is_set = match self.target {
             BitwiseTestTarget::Register(reg) => {
                 let value = cpu.registers.get_byte(reg);
@@ -108,7 +107,7 @@ impl Execute for BitwiseTest {
             }
             BitwiseTestTarget::PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer);
-                let value = memory.read_byte(addr);
+                let value = cpu.read(bus, addr);
 
                 self.bit.is_set(value)
             }
@@ -123,12 +122,12 @@ impl Execute for BitwiseTest {
 }
 
 impl Execute for BitwiseAnd {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         let value = match self.target {
             BitwiseAndTarget::Register(reg) => cpu.registers.get_byte(reg),
             BitwiseAndTarget::PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer);
-                memory.read_byte(addr)
+                cpu.read(bus, addr)
             }
             BitwiseAndTarget::Data(data) => *data,
         };
@@ -144,12 +143,12 @@ impl Execute for BitwiseAnd {
 }
 
 impl Execute for BitwiseXor {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         let value = match self.target {
             BitwiseXorTarget::Register(reg) => cpu.registers.get_byte(reg),
             BitwiseXorTarget::PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer);
-                memory.read_byte(addr)
+                cpu.read(bus, addr)
             }
             BitwiseXorTarget::Data(data) => *data,
         };
@@ -165,12 +164,12 @@ impl Execute for BitwiseXor {
 }
 
 impl Execute for BitwiseOr {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         let value = match self.target {
             BitwiseOrTarget::Register(reg) => cpu.registers.get_byte(reg),
             BitwiseOrTarget::PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer);
-                memory.read_byte(addr)
+                cpu.read(bus, addr)
             }
             BitwiseOrTarget::Data(data) => *data,
         };
@@ -185,12 +184,12 @@ impl Execute for BitwiseOr {
 }
 
 impl Execute for ShiftLeft {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         let value = match self.target {
             ShiftLeftTarget::Register(reg) => cpu.registers.get_byte(reg),
             ShiftLeftTarget::PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer);
-                memory.read_byte(addr)
+                cpu.read(bus, addr)
             }
         };
 
@@ -205,7 +204,7 @@ impl Execute for ShiftLeft {
             ShiftLeftTarget::Register(reg) => cpu.registers.set_byte(reg, value),
             ShiftLeftTarget::PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer);
-                memory.write_byte(addr, value);
+                cpu.write(bus, addr, value);
             }
         };
 
@@ -214,12 +213,12 @@ impl Execute for ShiftLeft {
 }
 
 impl Execute for ShiftRight {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         let value = match self.target {
             ShiftRightTarget::Register(reg) => cpu.registers.get_byte(reg),
             ShiftRightTarget::PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer);
-                memory.read_byte(addr)
+                cpu.read(bus, addr)
             }
         };
 
@@ -234,7 +233,7 @@ impl Execute for ShiftRight {
             ShiftRightTarget::Register(reg) => cpu.registers.set_byte(reg, value),
             ShiftRightTarget::PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer);
-                memory.write_byte(addr, value);
+                cpu.write(bus, addr, value);
             }
         };
 
@@ -243,12 +242,12 @@ impl Execute for ShiftRight {
 }
 
 impl Execute for Swap {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         let value = match self.target {
             SwapTarget::Register(reg) => cpu.registers.get_byte(reg),
             SwapTarget::PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer);
-                memory.read_byte(addr)
+                cpu.read(bus, addr)
             }
         };
 
@@ -261,7 +260,7 @@ impl Execute for Swap {
             SwapTarget::Register(reg) => cpu.registers.set_byte(reg, value),
             SwapTarget::PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer);
-                memory.write_byte(addr, value);
+                cpu.write(bus, addr, value);
             }
         };
 
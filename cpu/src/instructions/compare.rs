@@ -1,19 +1,18 @@
 use super::{Effect, Execute};
-use crate::Cpu;
+use crate::{bus::Bus, Cpu};
 use gb_rs_asm::containers::{Cycles, Flag};
 use gb_rs_asm::operations::compare::*;
 use gb_rs_common::Z80Sub;
-use gb_rs_memory::Memory;
 
 impl Execute for Compare {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         use CompareTarget::*;
 
         let rhs = match self.target {
             Register(reg) => cpu.registers.get_byte(reg),
             PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer);
-                memory.read_byte(addr)
+                cpu.read(bus, addr)
             }
             Data(data) => *data,
         };
@@ -1,11 +1,10 @@
 use super::{Effect, Execute};
-use crate::{enum_pass_execute, Cpu};
+use crate::{bus::Bus, enum_pass_execute, Cpu};
 use gb_rs_asm::containers::{Cycles, Flag};
 use gb_rs_asm::instructions::decrement::{
     Decrement, PairDecrement, PairPointerDecrement, RegisterDecrement,
 };
 use gb_rs_common::Z80Sub;
-use gb_rs_memory::Memory;
 
 impl Execute for Decrement {
     enum_pass_execute!(
@@ -16,7 +15,7 @@ impl Execute for Decrement {
 }
 
 impl Execute for RegisterDecrement {
-    fn execute(self, cpu: &mut Cpu, _memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, _bus: &mut B, cycles: Cycles) -> Effect {
         let output = cpu.registers.get_byte(self.target).sub_with_flags(1);
         cpu.registers.set_byte(self.target, output.result);
 
@@ -32,19 +31,20 @@ impl Execute for RegisterDecrement {
 }
 
 impl Execute for PairDecrement {
-    fn execute(self, cpu: &mut Cpu, _memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         let output = cpu.registers.get_pair(self.target).sub_with_flags(1);
         cpu.registers.set_pair(self.target, output.result);
+        cpu.idle(bus);
 
         cycles.into()
     }
 }
 
 impl Execute for PairPointerDecrement {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         let addr = cpu.registers.get_pair(*self.target);
-        let output = memory.read_byte(addr).sub_with_flags(1);
-        memory.write_byte(addr, output.result);
+        let output = cpu.read(bus, addr).sub_with_flags(1);
+        cpu.write(bus, addr, output.result);
 
         cpu.registers.flags.set(Flag::Subtract);
         cpu.registers.flags.set_if(Flag::Zero, output.result == 0);
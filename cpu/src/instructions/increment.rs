@@ -1,11 +1,10 @@
 use super::{Effect, Execute};
-use crate::{enum_pass_execute, Cpu};
+use crate::{bus::Bus, enum_pass_execute, Cpu};
 use gb_rs_asm::containers::{Cycles, Flag};
 use gb_rs_asm::instructions::increment::{
     Increment, PairIncrement, PairPointerIncrement, RegisterIncrement,
 };
 use gb_rs_common::Z80Add;
-use gb_rs_memory::Memory;
 
 impl Execute for Increment {
     enum_pass_execute!(
@@ -16,16 +15,17 @@ impl Execute for Increment {
 }
 
 impl Execute for PairIncrement {
-    fn execute(self, cpu: &mut Cpu, _memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         let value = cpu.registers.get_pair(self.target).wrapping_add(1);
         cpu.registers.set_pair(self.target, value);
+        cpu.idle(bus);
 
         cycles.into()
     }
 }
 
 impl Execute for RegisterIncrement {
-    fn execute(self, cpu: &mut Cpu, _memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, _bus: &mut B, cycles: Cycles) -> Effect {
         let output = cpu.registers.get_byte(self.target).add_with_flags(1);
         cpu.registers.set_byte(self.target, output.result);
 
@@ -41,10 +41,10 @@ impl Execute for RegisterIncrement {
 }
 
 impl Execute for PairPointerIncrement {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         let addr = cpu.registers.get_pair(*self.target);
-        let output = memory.read_byte(addr).add_with_flags(1);
-        memory.write_byte(addr, output.result);
+        let output = cpu.read(bus, addr).add_with_flags(1);
+        cpu.write(bus, addr, output.result);
 
         cpu.registers.flags.unset(Flag::Subtract);
         cpu.registers.flags.set_if(Flag::Zero, output.is_zero());
@@ -1,14 +1,13 @@
 use super::{Effect, Execute};
-use crate::{enum_pass_execute, ConditionTest, Cpu};
+use crate::{bus::Bus, enum_pass_execute, ConditionTest, Cpu};
 use gb_rs_asm::{containers::Cycles, operations::jump::*};
-use gb_rs_memory::Memory;
 
 impl Execute for Jump {
     enum_pass_execute!(Self::Absolute(inner), Self::Relative(inner));
 }
 
 impl Execute for AbsoluteJump {
-    fn execute(self, cpu: &mut Cpu, _memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         use AbsoluteJumpTarget::*;
 
         let addr = match self.target {
@@ -19,6 +18,11 @@ impl Execute for AbsoluteJump {
         let jumped = if self.condition.test(&cpu.registers.flags) {
             cpu.registers.program_counter = addr;
 
+            // Jumping to an immediate address spends a cycle loading it into PC. `JP HL` doesn't.
+            if let DataPointer(_) = self.target {
+                cpu.idle(bus);
+            }
+
             true
         } else {
             false
@@ -31,10 +35,11 @@ impl Execute for AbsoluteJump {
 }
 
 impl Execute for RelativeJump {
-    fn execute(self, cpu: &mut Cpu, _memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         let jumped = if self.condition.test(&cpu.registers.flags) {
             let offset: i8 = self.offset.into();
             cpu.registers.update_pc(offset);
+            cpu.idle(bus);
 
             true
         } else {
@@ -1,10 +1,9 @@
 use super::{Effect, Execute};
-use crate::{enum_pass_execute, Cpu};
+use crate::{bus::Bus, enum_pass_execute, Cpu};
 use gb_rs_asm::containers::Cycles;
 use gb_rs_asm::instructions::load::{Action, DataPointerLoadSource, RegisterPointerLoad};
 use gb_rs_asm::operations::load::*;
 use gb_rs_common::Z80Add;
-use gb_rs_memory::Memory;
 
 impl Execute for Load {
     enum_pass_execute!(
@@ -17,13 +16,18 @@ impl Execute for Load {
 }
 
 impl Execute for PairLoad {
-    fn execute(self, cpu: &mut Cpu, _memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         use PairLoadSource::*;
 
         let value = match self.source {
             Data(data) => *data,
-            Pair(source) => cpu.registers.get_pair(source),
+            Pair(source) => {
+                cpu.idle(bus);
+                cpu.registers.get_pair(source)
+            }
             SignedData(data) => {
+                cpu.idle(bus);
+
                 let initial_value = cpu.registers.stack_pointer;
                 let output = initial_value.add_with_flags(data.as_twos_complement());
 
@@ -41,7 +45,7 @@ impl Execute for PairLoad {
 }
 
 impl Execute for PairPointerLoad {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         use PairPointerLoadSource::*;
 
         let value = match self.source {
@@ -49,7 +53,7 @@ impl Execute for PairPointerLoad {
             Register(source) => cpu.registers.get_byte(source),
         };
 
-        memory.write_byte(cpu.registers.get_pair(*self.target), value);
+        cpu.write(bus, cpu.registers.get_pair(*self.target), value);
 
         match self.action {
             Action::Increment => {
@@ -68,26 +72,26 @@ impl Execute for PairPointerLoad {
 }
 
 impl Execute for RegisterLoad {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         use RegisterLoadSource::*;
 
         let value = match self.source {
             Data(n) => *n,
             PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer.source);
-                memory.read_byte(addr)
+                cpu.read(bus, addr)
             }
             Register(source) => cpu.registers.get_byte(source),
-            DataPointer(pointer) => memory.read_byte(pointer.into()),
+            DataPointer(pointer) => cpu.read(bus, pointer.into()),
             HighDataPointer(pointer) => {
                 let value: u16 = pointer.into();
                 let addr = 0xFF00 + value;
 
-                memory.read_byte(addr)
+                cpu.read(bus, addr)
             }
             RegisterPointer(pointer) => {
                 let reg: u16 = cpu.registers.get_byte(*pointer).into();
-                memory.read_byte(0xFF00 + reg)
+                cpu.read(bus, 0xFF00 + reg)
             }
         };
 
@@ -112,17 +116,17 @@ impl Execute for RegisterLoad {
 }
 
 impl Execute for RegisterPointerLoad {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         let reg: u16 = cpu.registers.c.into();
         let addr = 0xFF00 + reg;
-        memory.write_byte(addr, cpu.registers.a);
+        cpu.write(bus, addr, cpu.registers.a);
 
         cycles.into()
     }
 }
 
 impl Execute for DataPointerLoad {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         use DataPointerLoadTarget::*;
 
         let target_addr = match self.target {
@@ -135,10 +139,10 @@ impl Execute for DataPointerLoad {
 
         match self.source {
             DataPointerLoadSource::Register(source) => {
-                memory.write_byte(target_addr, cpu.registers.get_byte(source));
+                cpu.write(bus, target_addr, cpu.registers.get_byte(source));
             }
             DataPointerLoadSource::Pair(source) => {
-                memory.write_word(target_addr, cpu.registers.get_pair(source));
+                cpu.write_word(bus, target_addr, cpu.registers.get_pair(source));
             }
         };
 
@@ -1,6 +1,9 @@
-use crate::Cpu;
-use gb_rs_asm::{containers::Cycles, operations::OperationKind};
-use gb_rs_memory::Memory;
+use crate::{bus::Bus, is_button_pressed, Cpu, State, SPEED_SWITCH_CYCLES};
+use gb_rs_asm::{
+    containers::{Cycles, Flag},
+    operations::OperationKind,
+};
+use gb_rs_memory::constants::DIVIDER;
 use std::convert::TryInto;
 
 mod add;
@@ -34,20 +37,20 @@ impl From<Cycles> for Effect {
 }
 
 pub trait Execute {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect;
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect;
 }
 
 #[macro_export(local_inner_macros)]
 macro_rules! enum_pass_execute {
     ( $( $enum:ident :: $variant:ident $( ($inner:ident) )? $( => $retval:expr )? $(,)? ),* ) => {
-        fn execute(
+        fn execute<B: $crate::bus::Bus>(
             self,
             cpu: &mut $crate::Cpu,
-            memory: &mut gb_rs_memory::Memory,
+            bus: &mut B,
             cycles: gb_rs_asm::containers::Cycles,
         ) -> $crate::instructions::Effect {
             match self {
-                $( $enum::$variant $( ($inner) )? => parse_pass_arm_rhs!( cpu, memory, cycles => $( $inner )? $( $retval )? ) ),*
+                $( $enum::$variant $( ($inner) )? => parse_pass_arm_rhs!( cpu, bus, cycles => $( $inner )? $( $retval )? ) ),*
             }
         }
     };
@@ -55,54 +58,129 @@ macro_rules! enum_pass_execute {
 
 #[macro_export]
 macro_rules! parse_pass_arm_rhs {
-    ( $cpu:ident, $memory:ident, $cycles:ident => $inner:ident ) => {
-        $inner.execute($cpu, $memory, $cycles)
+    ( $cpu:ident, $bus:ident, $cycles:ident => $inner:ident ) => {
+        $inner.execute($cpu, $bus, $cycles)
     };
 
-    ( $cpu:ident, $memory:ident, $cycles:ident => $retval:expr ) => {
+    ( $cpu:ident, $bus:ident, $cycles:ident => $retval:expr ) => {
         $retval
     };
 }
 
 impl Execute for OperationKind {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         match self {
             Self::Nop => Effect { cycles: 1 },
-            Self::Stop => do_stop(cpu, memory),
-            Self::Halt => do_halt(cpu, memory),
-            Self::DecimalAdjust => do_decimal_adjust(cpu, memory),
+            Self::Stop => do_stop(cpu, bus),
+            Self::Halt => do_halt(cpu, bus),
+            Self::DecimalAdjust => do_decimal_adjust(cpu, bus),
             Self::DisableInterrupts => {
                 cpu.interrupts_enabled = false;
+                cpu.enable_interrupts_pending = false;
+
                 Effect { cycles: 1 }
             }
             Self::EnableInterrupts => {
-                cpu.interrupts_enabled = true;
+                // IME isn't set until after the next instruction; see [`Cpu::step()`].
+                cpu.enable_interrupts_pending = true;
+
                 Effect { cycles: 1 }
             }
-            Self::Load(inner) => inner.execute(cpu, memory, cycles),
-            Self::Increment(inner) => inner.execute(cpu, memory, cycles),
-            Self::Decrement(inner) => inner.execute(cpu, memory, cycles),
-            Self::RotateLeft(inner) => inner.execute(cpu, memory, cycles),
-            Self::RotateRight(inner) => inner.execute(cpu, memory, cycles),
-            Self::Add(inner) => inner.execute(cpu, memory, cycles),
-            Self::Subtract(inner) => inner.execute(cpu, memory, cycles),
-            Self::Compare(inner) => inner.execute(cpu, memory, cycles),
-            Self::Jump(inner) => inner.execute(cpu, memory, cycles),
-            Self::Bit(inner) => inner.execute(cpu, memory, cycles),
-            Self::Subroutine(inner) => inner.execute(cpu, memory, cycles),
-            Self::Stack(inner) => inner.execute(cpu, memory, cycles),
+            Self::Load(inner) => inner.execute(cpu, bus, cycles),
+            Self::Increment(inner) => inner.execute(cpu, bus, cycles),
+            Self::Decrement(inner) => inner.execute(cpu, bus, cycles),
+            Self::RotateLeft(inner) => inner.execute(cpu, bus, cycles),
+            Self::RotateRight(inner) => inner.execute(cpu, bus, cycles),
+            Self::Add(inner) => inner.execute(cpu, bus, cycles),
+            Self::Subtract(inner) => inner.execute(cpu, bus, cycles),
+            Self::Compare(inner) => inner.execute(cpu, bus, cycles),
+            Self::Jump(inner) => inner.execute(cpu, bus, cycles),
+            Self::Bit(inner) => inner.execute(cpu, bus, cycles),
+            Self::Subroutine(inner) => inner.execute(cpu, bus, cycles),
+            Self::Stack(inner) => inner.execute(cpu, bus, cycles),
         }
     }
 }
 
-fn do_stop(_cpu: &mut Cpu, _memory: &mut Memory) -> Effect {
-    todo!()
+/// Executes `STOP`, following the rules laid out in the
+/// [Pan Docs](https://gbdev.io/pandocs/Reducing_Power_Consumption.html#the-bizarre-case-of-the-game-boy-stop-instruction-before-even-considering-timing).
+///
+/// `STOP` is technically a two byte instruction, but the second byte is only skipped in some
+/// cases, so the instruction set treats it as a single byte and PC is moved forward here instead.
+fn do_stop<B: Bus>(cpu: &mut Cpu, bus: &mut B) -> Effect {
+    let interrupt_pending = bus.pending_interrupts() != 0;
+
+    if is_button_pressed(bus) {
+        // With a button held, STOP mode can't be entered, since it would immediately be exited.
+        // Instead, the CPU either does nothing or enters HALT mode, and DIV is not reset.
+        if !interrupt_pending {
+            cpu.registers.update_pc(1u8);
+            cpu.state = State::Halted;
+        }
+
+        return Effect { cycles: 1 };
+    }
+
+    if !interrupt_pending {
+        cpu.registers.update_pc(1u8);
+    }
+
+    bus.write_byte(DIVIDER as u16, 0);
+
+    cpu.state = if bus.switch_speed() {
+        State::SwitchingSpeed(SPEED_SWITCH_CYCLES)
+    } else {
+        State::Stopped
+    };
+
+    Effect { cycles: 1 }
 }
 
-fn do_halt(_cpu: &mut Cpu, _memory: &mut Memory) -> Effect {
-    todo!()
+fn do_halt<B: Bus>(cpu: &mut Cpu, bus: &mut B) -> Effect {
+    if !cpu.interrupts_enabled && bus.pending_interrupts() != 0 {
+        // The HALT bug: HALT is exited immediately, but the CPU fails to increment PC when it
+        // reads the next opcode. See `HaltBugRead` for how this is handled.
+        cpu.halt_bug = true;
+    } else {
+        cpu.state = State::Halted;
+    }
+
+    Effect { cycles: 1 }
 }
 
-fn do_decimal_adjust(_cpu: &mut Cpu, _memory: &mut Memory) -> Effect {
-    todo!()
+/// Executes `DAA`, which adjusts the accumulator so that it contains a valid binary-coded decimal
+/// (BCD) value after a BCD addition or subtraction.
+///
+/// The correction depends on whether the previous operation was a subtraction (the
+/// [`Flag::Subtract`] flag), and whether it carried out of either nibble.
+fn do_decimal_adjust<B: Bus>(cpu: &mut Cpu, _bus: &mut B) -> Effect {
+    let flags = &mut cpu.registers.flags;
+    let value = cpu.registers.a;
+
+    let subtract = flags.has(Flag::Subtract);
+    let mut carry = flags.has(Flag::Carry);
+    let mut correction = 0;
+
+    if flags.has(Flag::HalfCarry) || (!subtract && value & 0x0F > 0x09) {
+        correction |= 0x06;
+    }
+
+    if carry || (!subtract && value > 0x99) {
+        correction |= 0x60;
+        carry = true;
+    }
+
+    let value = if subtract {
+        value.wrapping_sub(correction)
+    } else {
+        value.wrapping_add(correction)
+    };
+
+    flags.set_if(Flag::Zero, value == 0);
+    flags.unset(Flag::HalfCarry);
+    flags.set_if(Flag::Carry, carry);
+
+    cpu.registers.a = value;
+
+    Effect { cycles: 1 }
 }
@@ -1,25 +1,24 @@
 use super::{Effect, Execute};
-use crate::{enum_pass_execute, Cpu};
+use crate::{bus::Bus, enum_pass_execute, Cpu};
 use gb_rs_asm::containers::{Cycles, Flag};
 use gb_rs_asm::instructions::rotate_left::{
     CarryingRotateLeft, CarryingRotateLeftTarget, CyclicRotateLeft, CyclicRotateLeftTarget,
     RotateLeft,
 };
-use gb_rs_memory::Memory;
 
 impl Execute for RotateLeft {
     enum_pass_execute!(Self::Cylic(inner), Self::Carrying(inner));
 }
 
 impl Execute for CyclicRotateLeft {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         use CyclicRotateLeftTarget::*;
 
         let value = match self.target {
             Register(reg) => cpu.registers.get_byte(reg),
             PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer);
-                memory.read_byte(addr)
+                cpu.read(bus, addr)
             }
         };
 
@@ -36,7 +35,7 @@ impl Execute for CyclicRotateLeft {
             Register(reg) => cpu.registers.set_byte(reg, value),
             PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer);
-                memory.write_byte(addr, value);
+                cpu.write(bus, addr, value);
             }
         };
 
@@ -45,14 +44,14 @@ impl Execute for CyclicRotateLeft {
 }
 
 impl Execute for CarryingRotateLeft {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         use CarryingRotateLeftTarget::*;
 
         let value = match self.target {
             Register(reg) => cpu.registers.get_byte(reg),
             PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer);
-                memory.read_byte(addr)
+                cpu.read(bus, addr)
             }
         };
 
@@ -70,7 +69,7 @@ impl Execute for CarryingRotateLeft {
             Register(reg) => cpu.registers.set_byte(reg, value),
             PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer);
-                memory.write_byte(addr, value);
+                cpu.write(bus, addr, value);
             }
         };
 
@@ -1,22 +1,21 @@
 use super::{Effect, Execute};
-use crate::{enum_pass_execute, Cpu};
+use crate::{bus::Bus, enum_pass_execute, Cpu};
 use gb_rs_asm::containers::{Cycles, Flag};
 use gb_rs_asm::instructions::rotate_right::*;
-use gb_rs_memory::Memory;
 
 impl Execute for RotateRight {
     enum_pass_execute!(Self::Cyclic(inner), Self::Carrying(inner));
 }
 
 impl Execute for CyclicRotateRight {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         use CyclicRotateRightTarget::*;
 
         let value = match self.target {
             Register(reg) => cpu.registers.get_byte(reg),
             PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer);
-                memory.read_byte(addr)
+                cpu.read(bus, addr)
             }
         };
 
@@ -36,7 +35,7 @@ impl Execute for CyclicRotateRight {
             Register(reg) => cpu.registers.set_byte(reg, value),
             PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer);
-                memory.write_byte(addr, value);
+                cpu.write(bus, addr, value);
             }
         };
 
@@ -45,14 +44,14 @@ impl Execute for CyclicRotateRight {
 }
 
 impl Execute for CarryingRotateRight {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         use CarryingRotateRightTarget::*;
 
         let value = match self.target {
             Register(reg) => cpu.registers.get_byte(reg),
             PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer);
-                memory.read_byte(addr)
+                cpu.read(bus, addr)
             }
         };
 
@@ -74,7 +73,7 @@ impl Execute for CarryingRotateRight {
             Register(reg) => cpu.registers.set_byte(reg, value),
             PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer);
-                memory.write_byte(addr, value);
+                cpu.write(bus, addr, value);
             }
         }
 
@@ -1,15 +1,14 @@
 use super::{Effect, Execute};
-use crate::{enum_pass_execute, Cpu};
+use crate::{bus::Bus, enum_pass_execute, Cpu};
 use gb_rs_asm::{containers::Cycles, instructions::stack::*};
 use gb_rs_common::bytes::{bytes_to_word, word_to_bytes};
-use gb_rs_memory::Memory;
 
 impl Execute for Stack {
     enum_pass_execute!(Self::Push(inner), Self::Pop(inner));
 }
 
 impl Execute for PushStack {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         use PushStackTarget::*;
 
         let value = match self.target {
@@ -17,17 +16,16 @@ impl Execute for PushStack {
             AccumulatorAndFlags => bytes_to_word(cpu.registers.a, *cpu.registers.flags),
         };
 
-        cpu.registers.stack_pointer -= 2;
-        memory.write_word(cpu.registers.stack_pointer, value);
+        cpu.idle(bus);
+        cpu.push(bus, value);
 
         cycles.into()
     }
 }
 
 impl Execute for PopStack {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
-        let value = memory.read_word(cpu.registers.stack_pointer);
-        cpu.registers.stack_pointer += 2;
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
+        let value = cpu.pop(bus);
 
         use PopStackTarget::*;
 
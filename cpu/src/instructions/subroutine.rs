@@ -1,27 +1,31 @@
 use super::{Effect, Execute};
-use crate::{enum_pass_execute, ConditionTest, Cpu};
+use crate::{bus::Bus, enum_pass_execute, ConditionTest, Cpu};
 use gb_rs_asm::{
-    containers::Cycles,
+    containers::{Condition, Cycles},
     instructions::subroutine::Return,
-    operations::subroutine::{Call, Subroutine},
+    operations::subroutine::{Call, CallTarget, Subroutine},
 };
-use gb_rs_memory::Memory;
 
 impl Execute for Subroutine {
     enum_pass_execute!(Self::Return(inner), Self::Call(inner));
 }
 
 impl Execute for Return {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
+        // Conditional returns spend an extra cycle checking the condition.
+        if self.condition != Condition::Always {
+            cpu.idle(bus);
+        }
+
         let branched = if self.condition.test(&cpu.registers.flags) {
-            let target_addr = memory.read_word(cpu.registers.stack_pointer);
-            cpu.registers.stack_pointer += 2;
+            let target_addr = cpu.pop(bus);
 
             if self.enable_interrupt {
                 cpu.interrupts_enabled = true;
             }
 
             cpu.registers.program_counter = target_addr;
+            cpu.idle(bus);
 
             true
         } else {
@@ -35,14 +39,15 @@ impl Execute for Return {
 }
 
 impl Execute for Call {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         let branched = if self.condition.test(&cpu.registers.flags) {
-            cpu.registers.stack_pointer -= 2;
-            memory.write_word(cpu.registers.stack_pointer, cpu.registers.program_counter);
+            let target_addr = match self.target {
+                CallTarget::DataPointer(pointer) => **pointer,
+                CallTarget::Vector(pointer) => *pointer,
+            };
 
-            // PC is updated BEFORE [`Execute::execute()`] is called, so we need to step back two
-            // bytes to properly load the target address.
-            let target_addr = memory.read_word(cpu.registers.program_counter - 2);
+            cpu.idle(bus);
+            cpu.push(bus, cpu.registers.program_counter);
             cpu.registers.program_counter = target_addr;
 
             true
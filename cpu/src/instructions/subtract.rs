@@ -1,25 +1,24 @@
 use super::{Effect, Execute};
-use crate::{enum_pass_execute, Cpu};
+use crate::{bus::Bus, enum_pass_execute, Cpu};
 use gb_rs_asm::{
     containers::{Cycles, Flag},
     operations::subtract::*,
 };
 use gb_rs_common::Z80Sub;
-use gb_rs_memory::Memory;
 
 impl Execute for Subtract {
     enum_pass_execute!(Self::Register(inner));
 }
 
 impl Execute for RegisterSubtract {
-    fn execute(self, cpu: &mut Cpu, memory: &mut Memory, cycles: Cycles) -> Effect {
+    fn execute<B: Bus>(self, cpu: &mut Cpu, bus: &mut B, cycles: Cycles) -> Effect {
         use RegisterSubtractSource::*;
 
         let rhs = match self.source {
             Register(reg) => cpu.registers.get_byte(reg),
             PairPointer(pointer) => {
                 let addr = cpu.registers.get_pair(*pointer);
-                memory.read_byte(addr)
+                cpu.read(bus, addr)
             }
             Data(data) => *data,
         };
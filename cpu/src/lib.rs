@@ -1,11 +1,18 @@
-use gb_rs_asm::{containers::Condition, sets::Instructions};
-use gb_rs_common::DeviceMode;
-use gb_rs_memory::Memory;
+use bus::{Bus, BusRead};
+use gb_rs_asm::{containers::Condition, parse, read::Read, sets::Instructions};
+use gb_rs_common::{
+    bytes::{bytes_to_word, word_to_bytes},
+    clock::Clock,
+    interrupts::Interrupt,
+    model::{BootHeader, Model},
+};
+use gb_rs_memory::constants::JOYPAD;
 use inspector::{Inspector, Message};
 use instructions::{Effect, Execute};
 use registers::{FlagsRegister, Registers};
 use std::sync::mpsc::Receiver;
 
+pub mod bus;
 pub mod inspector;
 pub mod instructions;
 pub mod registers;
@@ -14,18 +21,78 @@ pub mod registers;
 pub struct Cpu {
     pub registers: Registers,
     pub instructions: Instructions,
-    pub cycle_counter: u16,
+    pub clock: Clock,
     pub interrupts_enabled: bool,
+    pub state: State,
+    halt_bug: bool,
+    enable_interrupts_pending: bool,
     inspector: Inspector,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    Running,
+
+    /// Entered by `HALT`. The CPU does nothing until an interrupt is pending.
+    Halted,
+
+    /// Entered by `STOP`. The CPU and system clock are stopped until a button is pressed.
+    Stopped,
+
+    /// Entered by `STOP` when a CGB speed switch was armed. The CPU is paused for the contained
+    /// number of cycles while the clock switches speed.
+    SwitchingSpeed(u16),
+
+    /// Entered when the CPU tries to execute an illegal opcode. On real hardware, the CPU hangs
+    /// and stops responding to interrupts until the system is reset.
+    Locked {
+        pc: u16,
+    },
+}
+
+/// Describes what happened during a single call to [`Cpu::step()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepOutcome {
+    /// An instruction was executed.
+    Executed,
+
+    /// An interrupt was dispatched instead of executing an instruction.
+    ///
+    /// The contained value is `None` if the dispatch was cancelled because pushing PC to the
+    /// stack overwrote `IE`, in which case the CPU jumps to `$0000`.
+    Interrupted(Option<Interrupt>),
+
+    /// The CPU is halted, stopped, or switching speeds, and did nothing but wait.
+    Idle,
+
+    /// The CPU is locked up after trying to execute an illegal opcode at `pc`.
+    Locked { pc: u16 },
+}
+
+/// The number of cycles the CPU is paused for while performing a CGB speed switch.
+const SPEED_SWITCH_CYCLES: u16 = 2050;
+
 impl Cpu {
-    pub fn new(mode: DeviceMode) -> Self {
+    /// Creates a CPU in the state `model`'s boot ROM leaves it in after booting a cartridge with
+    /// the given header.
+    pub fn new(model: Model, header: &BootHeader) -> Self {
+        Self::with_registers(Registers::new(model, header))
+    }
+
+    /// Creates a CPU in its power on state, ready to execute a boot ROM from `$0000`.
+    pub fn power_on() -> Self {
+        Self::with_registers(Registers::power_on())
+    }
+
+    fn with_registers(registers: Registers) -> Self {
         Self {
             instructions: Instructions::default(),
-            cycle_counter: 0,
-            interrupts_enabled: true,
-            registers: Registers::new(mode),
+            clock: Clock::new(),
+            interrupts_enabled: false,
+            state: State::Running,
+            halt_bug: false,
+            enable_interrupts_pending: false,
+            registers,
             inspector: Inspector::new(),
         }
     }
@@ -34,13 +101,80 @@ impl Cpu {
         self.inspector.connect()
     }
 
-    pub fn step(&mut self, memory: &mut Memory) {
-        let operation = self
-            .instructions
-            .parse(memory, self.registers.program_counter)
-            .unwrap();
+    pub fn step<B: Bus>(&mut self, bus: &mut B) -> Result<StepOutcome, CpuError> {
+        match self.state {
+            State::Running => (),
+            State::Halted => {
+                if bus.pending_interrupts() == 0 {
+                    self.tick(bus, 1);
+                    return Ok(StepOutcome::Idle);
+                }
+
+                self.state = State::Running;
+            }
+            State::Stopped => {
+                if !is_button_pressed(bus) {
+                    return Ok(StepOutcome::Idle);
+                }
+
+                self.state = State::Running;
+            }
+            State::SwitchingSpeed(remaining) => {
+                self.tick(bus, 1);
+
+                self.state = match remaining.saturating_sub(1) {
+                    0 => State::Running,
+                    n => State::SwitchingSpeed(n),
+                };
+
+                return Ok(StepOutcome::Idle);
+            }
+            State::Locked { pc } => {
+                self.tick(bus, 1);
+                return Ok(StepOutcome::Locked { pc });
+            }
+        };
+
+        // The CPU is paused while another component (i.e. VRAM DMA) has control of the bus, but
+        // the rest of the system keeps running.
+        let stall = bus.take_stall_cycles();
+
+        if stall > 0 {
+            for _ in 0..stall {
+                self.tick(bus, 1);
+            }
+
+            return Ok(StepOutcome::Idle);
+        }
+
+        if let Some(interrupt) = self.service_interrupt(bus) {
+            return Ok(StepOutcome::Interrupted(interrupt));
+        }
+
+        // EI only takes effect after the instruction following it has executed, so we need to
+        // capture whether it was pending before this instruction runs. A DI in between cancels it.
+        let enable_interrupts = self.enable_interrupts_pending;
 
         let pc = self.registers.program_counter;
+        let halt_bug = std::mem::take(&mut self.halt_bug);
+
+        let reader = BusRead(&*bus);
+        let parsed = if halt_bug {
+            self.instructions.parse(&HaltBugRead::new(&reader, pc), pc)
+        } else {
+            self.instructions.parse(&reader, pc)
+        };
+
+        let operation = match parsed {
+            Ok(operation) => operation,
+            Err(parse::Error::UnknownOpcode(_)) => {
+                self.state = State::Locked { pc };
+                self.tick(bus, 1);
+
+                return Ok(StepOutcome::Locked { pc });
+            }
+            Err(parse::Error::Read(source)) => return Err(CpuError::Read { pc, source }),
+        };
 
         self.inspector.send_fn(|| Message::Operation {
             op: operation.clone(),
@@ -49,19 +183,182 @@ impl Cpu {
 
         // PC needs to be updated BEFORE executing the instruction, otherwise we end up with
         // inconsistent positioning if the instruction changes PC, e.g. during a relative jump.
-        self.registers.update_pc(operation.width);
+        //
+        // If the HALT bug was triggered, the CPU fails to increment PC after reading the opcode,
+        // so the instruction ends up one byte shorter than it should be.
+        self.registers.update_pc(operation.width - halt_bug as u8);
+
+        // Fetching the opcode and its operands takes a cycle per byte. Any reads, writes and
+        // internal cycles the instruction performs are then ticked as they happen.
+        let start = self.clock.m_cycles();
+        self.tick(bus, operation.width);
+
+        let Effect { cycles } = operation.kind.execute(self, bus, operation.cycles);
 
-        let Effect { cycles } = operation.kind.execute(self, memory, operation.cycles);
-        self.update_cycles(cycles);
+        // Anything left over (i.e. an internal cycle that isn't modelled explicitly) is spent at
+        // the end of the instruction, so the total always matches the cycles table.
+        let elapsed = (self.clock.m_cycles() - start) as u8;
+        debug_assert!(
+            elapsed <= cycles,
+            "instruction at {:#06X} took {} cycles, expected {}",
+            pc,
+            elapsed,
+            cycles
+        );
+
+        self.tick(bus, cycles.saturating_sub(elapsed));
+
+        if enable_interrupts && self.enable_interrupts_pending {
+            self.enable_interrupts_pending = false;
+            self.interrupts_enabled = true;
+        }
 
         self.inspector.send(Message::Step);
+
+        Ok(StepOutcome::Executed)
+    }
+
+    /// Dispatches the highest priority pending interrupt, if `IME` is set.
+    ///
+    /// Servicing an interrupt takes 5 cycles: two idle cycles, two to push PC to the stack, and
+    /// one to jump to the interrupt's vector. Returns `None` if no dispatch took place, otherwise
+    /// returns the interrupt that was serviced (see [`StepOutcome::Interrupted`]).
+    fn service_interrupt<B: Bus>(&mut self, bus: &mut B) -> Option<Option<Interrupt>> {
+        if !self.interrupts_enabled || bus.pending_interrupts() == 0 {
+            return None;
+        }
+
+        self.interrupts_enabled = false;
+        self.idle(bus);
+        self.idle(bus);
+
+        let [high, low] = word_to_bytes(self.registers.program_counter);
+
+        self.registers.stack_pointer = self.registers.stack_pointer.wrapping_sub(1);
+        self.write(bus, self.registers.stack_pointer, high);
+
+        // The interrupt to service isn't chosen until after the high byte of PC is pushed. If
+        // that push overwrote IE, the interrupt may no longer be pending, in which case the CPU
+        // ends up jumping to $0000 instead.
+        let interrupt = Interrupt::highest_priority(bus.pending_interrupts());
+
+        self.registers.stack_pointer = self.registers.stack_pointer.wrapping_sub(1);
+        self.write(bus, self.registers.stack_pointer, low);
+
+        self.registers.program_counter = match interrupt {
+            Some(interrupt) => {
+                bus.acknowledge_interrupt(interrupt);
+                interrupt.vector()
+            }
+            None => 0x0000,
+        };
+
+        self.idle(bus);
+        self.inspector.send(Message::Step);
+
+        Some(interrupt)
     }
 
-    fn update_cycles<C>(&mut self, cycles: C)
-    where
-        C: Into<u16>,
-    {
-        self.cycle_counter = self.cycle_counter.wrapping_add(cycles.into());
+    /// Reads a byte from the bus, taking one cycle.
+    fn read<B: Bus>(&mut self, bus: &mut B, address: u16) -> u8 {
+        let value = bus.read_byte(address);
+        self.tick(bus, 1);
+
+        value
+    }
+
+    /// Writes a byte to the bus, taking one cycle.
+    fn write<B: Bus>(&mut self, bus: &mut B, address: u16, value: u8) {
+        bus.write_byte(address, value);
+        self.tick(bus, 1);
+    }
+
+    /// Reads a little-endian word from the bus, taking two cycles.
+    fn read_word<B: Bus>(&mut self, bus: &mut B, address: u16) -> u16 {
+        let low = self.read(bus, address);
+        let high = self.read(bus, address.wrapping_add(1));
+
+        bytes_to_word(high, low)
+    }
+
+    /// Writes a little-endian word to the bus, low byte first, taking two cycles.
+    fn write_word<B: Bus>(&mut self, bus: &mut B, address: u16, value: u16) {
+        let [high, low] = word_to_bytes(value);
+
+        self.write(bus, address, low);
+        self.write(bus, address.wrapping_add(1), high);
+    }
+
+    /// Pushes a word to the stack, high byte first, taking two cycles.
+    ///
+    /// This doesn't include the internal cycle spent decrementing SP; callers are responsible for
+    /// idling where the instruction requires it.
+    fn push<B: Bus>(&mut self, bus: &mut B, value: u16) {
+        let [high, low] = word_to_bytes(value);
+
+        self.registers.stack_pointer = self.registers.stack_pointer.wrapping_sub(1);
+        self.write(bus, self.registers.stack_pointer, high);
+        self.registers.stack_pointer = self.registers.stack_pointer.wrapping_sub(1);
+        self.write(bus, self.registers.stack_pointer, low);
+    }
+
+    /// Pops a word from the stack, taking two cycles.
+    fn pop<B: Bus>(&mut self, bus: &mut B) -> u16 {
+        let value = self.read_word(bus, self.registers.stack_pointer);
+        self.registers.stack_pointer = self.registers.stack_pointer.wrapping_add(2);
+
+        value
+    }
+
+    /// Spends one cycle without accessing the bus.
+    fn idle<B: Bus>(&mut self, bus: &mut B) {
+        self.tick(bus, 1);
+    }
+
+    /// Advances the rest of the system by `cycles`, and updates the clock to match.
+    fn tick<B: Bus>(&mut self, bus: &mut B, cycles: u8) {
+        bus.tick(cycles);
+        self.clock.advance(cycles.into(), bus.speed());
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CpuError {
+    #[error("could not read instruction at {pc:#06X}: {source}")]
+    Read {
+        pc: u16,
+        source: gb_rs_asm::read::Error,
+    },
+}
+
+/// Returns `true` if any button is held on a selected line of the joypad register.
+///
+/// Button states are active-low, so a held button reads back as a `0` in the lower nibble.
+pub fn is_button_pressed<B: Bus>(bus: &B) -> bool {
+    bus.read_byte(JOYPAD as u16) & 0x0F != 0x0F
+}
+
+/// Emulates the HALT bug by re-reading the opcode byte when reading an instruction's operands.
+///
+/// When `HALT` is executed with `IME` unset and an interrupt already pending, the CPU fails to
+/// increment PC after fetching the next opcode. The byte following the opcode is then read as
+/// the opcode itself, e.g. `LD A, $14` (`$3E $14`) is executed as `LD A, $3E` followed by
+/// `INC D` (`$14`).
+struct HaltBugRead<'a, R> {
+    inner: &'a R,
+    pc: u16,
+}
+
+impl<'a, R> HaltBugRead<'a, R> {
+    fn new(inner: &'a R, pc: u16) -> Self {
+        Self { inner, pc }
+    }
+}
+
+impl<R: Read> Read for HaltBugRead<'_, R> {
+    fn read_byte(&self, offset: u16) -> gb_rs_asm::read::Result<u8> {
+        let offset = if offset > self.pc { offset - 1 } else { offset };
+        self.inner.read_byte(offset)
     }
 }
 
@@ -80,3 +377,184 @@ impl ConditionTest for Condition {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gb_rs_memory::constants::{INTERRUPT_ENABLE, INTERRUPT_FLAGS};
+
+    /// A flat 64 KiB RAM, with a program loaded at the post-boot entry point of `$0100`.
+    struct Ram(Vec<u8>);
+
+    impl Ram {
+        fn with_program(program: &[u8]) -> Self {
+            let mut ram = vec![0; 0x10000];
+            ram[0x100..0x100 + program.len()].copy_from_slice(program);
+
+            Self(ram)
+        }
+    }
+
+    impl Bus for Ram {
+        fn read_byte(&self, address: u16) -> u8 {
+            self.0[address as usize]
+        }
+
+        fn write_byte(&mut self, address: u16, value: u8) {
+            self.0[address as usize] = value;
+        }
+    }
+
+    /// Records the cycle on which each write lands, counted from the first call to `tick()`.
+    struct WriteLog {
+        ram: Ram,
+        cycles: u32,
+        writes: Vec<(u32, u16)>,
+    }
+
+    impl WriteLog {
+        fn with_program(program: &[u8]) -> Self {
+            Self {
+                ram: Ram::with_program(program),
+                cycles: 0,
+                writes: Vec::new(),
+            }
+        }
+    }
+
+    impl Bus for WriteLog {
+        fn read_byte(&self, address: u16) -> u8 {
+            self.ram.read_byte(address)
+        }
+
+        fn write_byte(&mut self, address: u16, value: u8) {
+            self.writes.push((self.cycles, address));
+            self.ram.write_byte(address, value);
+        }
+
+        fn tick(&mut self, cycles: u8) {
+            self.cycles += u32::from(cycles);
+        }
+    }
+
+    fn step_n(cpu: &mut Cpu, bus: &mut Ram, count: usize) {
+        for _ in 0..count {
+            cpu.step(bus).unwrap();
+        }
+    }
+
+    #[test]
+    fn decimal_adjust() {
+        // LD A, $45; ADD A, $38; DAA
+        let mut bus = Ram::with_program(&[0x3E, 0x45, 0xC6, 0x38, 0x27]);
+        let mut cpu = Cpu::new(Model::Dmg, &BootHeader::default());
+
+        step_n(&mut cpu, &mut bus, 3);
+        assert_eq!(cpu.registers.a, 0x83);
+
+        // LD A, $10; DEC A; DAA
+        bus.0[0x105..0x109].copy_from_slice(&[0x3E, 0x10, 0x3D, 0x27]);
+
+        step_n(&mut cpu, &mut bus, 3);
+        assert_eq!(cpu.registers.a, 0x09);
+    }
+
+    #[test]
+    fn enable_interrupts_is_delayed() {
+        // EI; NOP; NOP
+        let mut bus = Ram::with_program(&[0xFB, 0x00, 0x00]);
+        bus.0[INTERRUPT_ENABLE] = Interrupt::Timer.mask();
+        bus.0[INTERRUPT_FLAGS] = Interrupt::Timer.mask();
+
+        let mut cpu = Cpu::new(Model::Dmg, &BootHeader::default());
+
+        assert_eq!(cpu.step(&mut bus).unwrap(), StepOutcome::Executed);
+        assert!(!cpu.interrupts_enabled);
+
+        assert_eq!(cpu.step(&mut bus).unwrap(), StepOutcome::Executed);
+        assert!(cpu.interrupts_enabled);
+
+        assert_eq!(
+            cpu.step(&mut bus).unwrap(),
+            StepOutcome::Interrupted(Some(Interrupt::Timer))
+        );
+
+        assert_eq!(cpu.registers.program_counter, 0x50);
+        assert_eq!(bus.read_word(cpu.registers.stack_pointer), 0x102);
+        assert_eq!(bus.0[INTERRUPT_FLAGS], 0);
+    }
+
+    #[test]
+    fn halt_bug() {
+        // HALT; LD A, $14
+        let mut bus = Ram::with_program(&[0x76, 0x3E, 0x14]);
+        bus.0[INTERRUPT_ENABLE] = Interrupt::VBlank.mask();
+        bus.0[INTERRUPT_FLAGS] = Interrupt::VBlank.mask();
+
+        let mut cpu = Cpu::new(Model::Dmg, &BootHeader::default());
+        let d = cpu.registers.d;
+
+        // The byte after HALT is read twice, so this runs LD A, $3E followed by INC D.
+        step_n(&mut cpu, &mut bus, 3);
+
+        assert_eq!(cpu.state, State::Running);
+        assert_eq!(cpu.registers.a, 0x3E);
+        assert_eq!(cpu.registers.d, d.wrapping_add(1));
+        assert_eq!(cpu.registers.program_counter, 0x103);
+    }
+
+    #[test]
+    fn illegal_opcode_locks_up() {
+        let mut bus = Ram::with_program(&[0xD3]);
+        let mut cpu = Cpu::new(Model::Dmg, &BootHeader::default());
+
+        for _ in 0..2 {
+            assert_eq!(
+                cpu.step(&mut bus).unwrap(),
+                StepOutcome::Locked { pc: 0x100 }
+            );
+        }
+
+        assert_eq!(cpu.state, State::Locked { pc: 0x100 });
+    }
+
+    #[test]
+    fn writes_land_on_their_own_cycles() {
+        // LD ($C000), SP
+        let mut bus = WriteLog::with_program(&[0x08, 0x00, 0xC0]);
+        let mut cpu = Cpu::new(Model::Dmg, &BootHeader::default());
+
+        cpu.step(&mut bus).unwrap();
+
+        assert_eq!(bus.writes, vec![(3, 0xC000), (4, 0xC001)]);
+        assert_eq!(bus.cycles, 5);
+
+        // PUSH BC
+        let mut bus = WriteLog::with_program(&[0xC5]);
+        let mut cpu = Cpu::new(Model::Dmg, &BootHeader::default());
+        let sp = cpu.registers.stack_pointer;
+
+        cpu.step(&mut bus).unwrap();
+
+        assert_eq!(bus.writes, vec![(2, sp - 1), (3, sp - 2)]);
+        assert_eq!(bus.cycles, 4);
+    }
+
+    #[test]
+    fn post_boot_registers_depend_on_model() {
+        let header = BootHeader {
+            header_checksum: 0x3C,
+            color: true,
+            ..Default::default()
+        };
+
+        let dmg = Registers::new(Model::Dmg, &header);
+        assert_eq!((dmg.a, *dmg.flags, dmg.b, dmg.c), (0x01, 0xB0, 0x00, 0x13));
+
+        let cgb = Registers::new(Model::Cgb, &header);
+        assert_eq!((cgb.a, *cgb.flags, cgb.b, cgb.d), (0x11, 0x80, 0x00, 0xFF));
+
+        let agb = Registers::new(Model::Agb, &header);
+        assert_eq!((agb.a, *agb.flags, agb.b, agb.d), (0x11, 0x00, 0x01, 0xFF));
+    }
+}
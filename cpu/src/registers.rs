@@ -1,7 +1,8 @@
 use gb_rs_asm::containers::{Flag, Pair, Register};
 use gb_rs_common::{
     bytes::{bytes_to_word, word_to_bytes},
-    DeviceMode, MathResult,
+    model::{BootHeader, Model},
+    MathResult, Z80Add,
 };
 use std::{
     fmt::{Display, Write},
@@ -125,28 +126,79 @@ pub struct Registers {
     pub stack_pointer: u16,
     pub program_counter: u16,
     pub flags: FlagsRegister,
-    pub stop_flag: bool,
 }
 
 impl Registers {
-    pub fn new(mode: DeviceMode) -> Self {
-        match mode {
-            DeviceMode::Classic => Self {
-                a: 0x11,
-                e: 0x08,
-                stack_pointer: 0xFFFE,
-                program_counter: 0x0100,
-                ..Default::default()
-            },
-            DeviceMode::Color => Self {
-                a: 0x11,
-                d: 0xFF,
-                e: 0x56,
-                l: 0x0D,
-                stack_pointer: 0xFFFE,
-                program_counter: 0x0100,
-                ..Default::default()
-            },
+    /// Creates registers in the state that `model`'s boot ROM leaves them in, which partly
+    /// depends on the cartridge header.
+    ///
+    /// See https://gbdev.io/pandocs/Power_Up_Sequence.html#cpu-registers.
+    pub fn new(model: Model, header: &BootHeader) -> Self {
+        // The DMG and MGB boot ROMs leave H and C set, unless the header checksum is $00.
+        let checksum_flags = if header.header_checksum == 0 {
+            0x80
+        } else {
+            0xB0
+        };
+
+        let [a, f, b, c, d, e, h, l] = match model {
+            Model::Dmg0 => [0x01, 0x00, 0xFF, 0x13, 0x00, 0xC1, 0x84, 0x03],
+            Model::Dmg => [0x01, checksum_flags, 0x00, 0x13, 0x00, 0xD8, 0x01, 0x4D],
+            Model::Mgb => [0xFF, checksum_flags, 0x00, 0x13, 0x00, 0xD8, 0x01, 0x4D],
+            Model::Sgb => [0x01, 0x00, 0x00, 0x14, 0x00, 0x00, 0xC0, 0x60],
+            Model::Sgb2 => [0xFF, 0x00, 0x00, 0x14, 0x00, 0x00, 0xC0, 0x60],
+            Model::Cgb | Model::Agb if header.color => {
+                [0x11, 0x80, 0x00, 0x00, 0xFF, 0x56, 0x00, 0x0D]
+            }
+            Model::Cgb | Model::Agb => {
+                // In compatibility mode, B holds the title checksum that was used to pick a
+                // palette (for Nintendo titles only), and HL depends on that checksum.
+                let b = if header.nintendo_licensee {
+                    header.title_checksum
+                } else {
+                    0x00
+                };
+
+                let [h, l] = match b {
+                    0x43 | 0x58 => [0x99, 0x1A],
+                    _ => [0x00, 0x7C],
+                };
+
+                [0x11, 0x80, b, 0x00, 0x00, 0x08, h, l]
+            }
+        };
+
+        let mut registers = Self {
+            a,
+            b,
+            c,
+            d,
+            e,
+            h,
+            l,
+            flags: f.into(),
+            stack_pointer: 0xFFFE,
+            program_counter: 0x0100,
+        };
+
+        // The AGB boot ROM is the CGB boot ROM, plus a final `INC B`.
+        if model == Model::Agb {
+            let output = registers.b.add_with_flags(1);
+
+            registers.b = output.result;
+            registers.flags.set_if(Flag::Zero, output.is_zero());
+            registers.flags.unset(Flag::Subtract);
+            registers.flags.set_if(Flag::HalfCarry, output.half_carry);
+        }
+
+        registers
+    }
+
+    /// Creates registers in their power on state, with everything (including PC) cleared.
+    pub fn power_on() -> Self {
+        Self {
+            flags: 0.into(),
+            ..Default::default()
         }
     }
 
@@ -0,0 +1,226 @@
+//! Runs the SingleStepTests SM83 test vectors (https://github.com/SingleStepTests/sm83) against
+//! [`Cpu::step()`].
+//!
+//! The vectors aren't vendored, since they're several hundred megabytes. To run this suite,
+//! clone the repository and point `SM83_TESTS_DIR` at the directory containing the `.json` files,
+//! e.g. `SM83_TESTS_DIR=../sm83/v1 cargo test -p gb_rs_cpu --test single_step -- --nocapture`.
+//! The suite is skipped if the variable isn't set.
+
+use gb_rs_common::model::{BootHeader, Model};
+use gb_rs_cpu::{bus::Bus, Cpu};
+use serde::Deserialize;
+use std::{fmt::Write, fs, path::Path};
+
+const TESTS_DIR_VAR: &str = "SM83_TESTS_DIR";
+
+#[derive(Debug, Deserialize)]
+struct TestCase {
+    name: String,
+    initial: State,
+    #[serde(rename = "final")]
+    expected: State,
+    cycles: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+struct State {
+    pc: u16,
+    sp: u16,
+    a: u8,
+    b: u8,
+    c: u8,
+    d: u8,
+    e: u8,
+    f: u8,
+    h: u8,
+    l: u8,
+    #[serde(default)]
+    ime: u8,
+    ram: Vec<(u16, u8)>,
+}
+
+/// A flat 64 KiB RAM that records the cycle each write lands on.
+struct TestBus {
+    ram: Vec<u8>,
+    cycles: usize,
+    writes: Vec<(usize, u16, u8)>,
+}
+
+impl TestBus {
+    fn new(ram: &[(u16, u8)]) -> Self {
+        let mut bus = Self {
+            ram: vec![0; 0x10000],
+            cycles: 0,
+            writes: Vec::new(),
+        };
+
+        for &(address, value) in ram {
+            bus.ram[address as usize] = value;
+        }
+
+        bus
+    }
+}
+
+impl Bus for TestBus {
+    fn read_byte(&self, address: u16) -> u8 {
+        self.ram[address as usize]
+    }
+
+    fn write_byte(&mut self, address: u16, value: u8) {
+        self.writes.push((self.cycles, address, value));
+        self.ram[address as usize] = value;
+    }
+
+    fn tick(&mut self, cycles: u8) {
+        self.cycles += cycles as usize;
+    }
+}
+
+/// Runs a single test case, returning a description of the first mismatch if it failed.
+fn run(case: &TestCase) -> Result<(), String> {
+    let initial = &case.initial;
+
+    let mut bus = TestBus::new(&initial.ram);
+    let mut cpu = Cpu::new(Model::Dmg, &BootHeader::default());
+
+    cpu.registers.program_counter = initial.pc;
+    cpu.registers.stack_pointer = initial.sp;
+    cpu.registers.a = initial.a;
+    cpu.registers.b = initial.b;
+    cpu.registers.c = initial.c;
+    cpu.registers.d = initial.d;
+    cpu.registers.e = initial.e;
+    cpu.registers.flags = initial.f.into();
+    cpu.registers.h = initial.h;
+    cpu.registers.l = initial.l;
+    cpu.interrupts_enabled = initial.ime != 0;
+
+    cpu.step(&mut bus).map_err(|e| e.to_string())?;
+
+    let actual = State {
+        pc: cpu.registers.program_counter,
+        sp: cpu.registers.stack_pointer,
+        a: cpu.registers.a,
+        b: cpu.registers.b,
+        c: cpu.registers.c,
+        d: cpu.registers.d,
+        e: cpu.registers.e,
+        f: *cpu.registers.flags,
+        h: cpu.registers.h,
+        l: cpu.registers.l,
+        ime: cpu.interrupts_enabled as u8,
+        ram: case
+            .expected
+            .ram
+            .iter()
+            .map(|&(address, _)| (address, bus.read_byte(address)))
+            .collect(),
+    };
+
+    if actual != case.expected {
+        return Err(format!("expected {:X?}, got {:X?}", case.expected, actual));
+    }
+
+    if bus.cycles != case.cycles.len() {
+        return Err(format!(
+            "expected {} cycles, took {}",
+            case.cycles.len(),
+            bus.cycles
+        ));
+    }
+
+    let expected_writes: Vec<_> = case
+        .cycles
+        .iter()
+        .enumerate()
+        .filter_map(|(cycle, access)| {
+            let access = access.as_array()?;
+            let kind = access.get(2)?.as_str()?;
+
+            if !kind.contains('w') {
+                return None;
+            }
+
+            let address = access.first()?.as_u64()? as u16;
+            let value = access.get(1)?.as_u64()? as u8;
+
+            Some((cycle, address, value))
+        })
+        .collect();
+
+    if bus.writes != expected_writes {
+        return Err(format!(
+            "expected writes (cycle, address, value) {:X?}, got {:X?}",
+            expected_writes, bus.writes
+        ));
+    }
+
+    Ok(())
+}
+
+/// Runs every case in `path`, printing a summary line and returning `false` if any failed.
+fn run_file(label: &str, path: &Path, failures: &mut String) -> bool {
+    let contents = fs::read_to_string(path).expect("could not read test file");
+    let cases: Vec<TestCase> = serde_json::from_str(&contents).expect("could not parse test file");
+
+    let mut first_failure = None;
+    let mut passed = 0;
+
+    for case in &cases {
+        match run(case) {
+            Ok(()) => passed += 1,
+            Err(e) => {
+                first_failure.get_or_insert_with(|| format!("{}: {}", case.name, e));
+            }
+        }
+    }
+
+    println!("{:<6} {:>5}/{:<5}", label, passed, cases.len());
+
+    if let Some(failure) = first_failure {
+        writeln!(failures, "{}: {}", label, failure).unwrap();
+        false
+    } else {
+        true
+    }
+}
+
+#[test]
+fn single_step_tests() {
+    let dir = match std::env::var_os(TESTS_DIR_VAR) {
+        Some(dir) => dir,
+        None => {
+            println!("{} is not set, skipping", TESTS_DIR_VAR);
+            return;
+        }
+    };
+
+    let dir = Path::new(&dir);
+    let mut failures = String::new();
+    let mut failed = 0;
+
+    let files = (0..=0xFFu8)
+        .map(|opcode| (format!("{:02X}", opcode), format!("{:02x}.json", opcode)))
+        .chain((0..=0xFFu8).map(|opcode| {
+            (
+                format!("CB {:02X}", opcode),
+                format!("cb {:02x}.json", opcode),
+            )
+        }));
+
+    for (label, file) in files {
+        let path = dir.join(file);
+
+        // Illegal opcodes (and the CB prefix itself) don't have test files.
+        if !path.exists() {
+            continue;
+        }
+
+        if !run_file(&label, &path, &mut failures) {
+            failed += 1;
+        }
+    }
+
+    assert!(failed == 0, "{} opcodes failed:\n{}", failed, failures);
+}
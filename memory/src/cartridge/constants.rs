@@ -1,3 +1,5 @@
+pub const LOGO_START: usize = 0x104;
+pub const LOGO_END: usize = 0x133;
 pub const TITLE_START: usize = 0x134;
 pub const GBC_SUPPORT_TYPE: usize = 0x143;
 pub const NEW_LICENSEE_HIGH: usize = 0x144;
@@ -7,3 +9,4 @@ pub const CONTROLLER_TYPE: usize = 0x147;
 pub const RAM_SIZE: usize = 0x149;
 pub const OLD_LICENSEE: usize = 0x14B;
 pub const VERSION: usize = 0x14C;
+pub const HEADER_CHECKSUM: usize = 0x14D;
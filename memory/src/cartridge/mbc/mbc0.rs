@@ -1,5 +1,5 @@
 use super::ControllerType;
-use crate::cartridge::mbc::MemoryBankController;
+use crate::cartridge::mbc::{import_ram, ImportError, MemoryBankController};
 
 pub struct Mbc0 {
     rom: Vec<u8>,
@@ -24,6 +24,18 @@ impl MemoryBankController for Mbc0 {
 
     fn ram_write(&mut self, _address: usize, _value: u8) {}
 
+    fn has_battery(&self) -> bool {
+        false
+    }
+
+    fn export_ram(&self) -> Vec<u8> {
+        Vec::new()
+    }
+
+    fn import_ram(&mut self, data: &[u8]) -> Result<(), ImportError> {
+        import_ram(&mut [], data)
+    }
+
     fn get_controller_type(&self) -> ControllerType {
         ControllerType::Mbc0
     }
@@ -1,14 +1,32 @@
 use super::ControllerType;
-use crate::cartridge::constants::RAM_SIZE;
-use crate::cartridge::mbc::{map_ram_address, map_rom_address};
+use crate::cartridge::constants::{LOGO_END, LOGO_START, RAM_SIZE};
+use crate::cartridge::mbc::{has_battery, import_ram, map_ram_address, ImportError};
 use crate::cartridge::{get_ram_size, MemoryBankController};
-use crate::constants::{EXTERNAL_RAM_SIZE, ROM0_END, ROM0_START, ROM_BANK_END, ROM_BANK_START};
+use crate::constants::{ROM0_END, ROM0_START, ROM_BANK_END, ROM_BANK_SIZE, ROM_BANK_START};
 
+/// The size of each game on an MBC1M multicart.
+const MULTICART_GAME_SIZE: usize = 0x10 * ROM_BANK_SIZE;
+
+/// MBC1, which supports up to 2 MiB of ROM and 32 KiB of RAM.
+///
+/// Banks are selected using two registers: `BANK1` holds the low 5 bits of the ROM bank mapped to
+/// `$4000-$7FFF`, and `BANK2` holds 2 more bits. What `BANK2` does depends on the banking mode:
+/// - In mode 0, it only provides the upper bits of the ROM bank mapped to `$4000-$7FFF`.
+/// - In mode 1, it also provides the upper bits of the ROM bank mapped to `$0000-$3FFF`, and
+///   selects the RAM bank.
+///
+/// MBC1M multicarts wire `BANK2` one bit lower, so only the low 4 bits of `BANK1` are used, and
+/// `BANK2` picks one of 4 games that are each 16 banks long.
 pub struct Mbc1 {
     rom: Vec<u8>,
     ram: Vec<u8>,
-    rom_bank: u8,
-    ram_bank: u8,
+    battery: bool,
+    multicart: bool,
+
+    /// The ROM bank bits that exist on the cart. Banks past the end of the ROM wrap around.
+    rom_bank_mask: usize,
+    bank1: u8,
+    bank2: u8,
     ram_enabled: bool,
     banking_mode_advanced: bool,
 }
@@ -17,16 +35,55 @@ impl Mbc1 {
     pub fn new(rom: Vec<u8>) -> Self {
         let ram_size = get_ram_size(rom[RAM_SIZE]).expect("Unsupported RAM size");
         let ram = vec![0; ram_size];
+        let battery = has_battery(&rom);
+        let multicart = is_multicart(&rom);
+        let rom_bank_mask = (rom.len() / ROM_BANK_SIZE).max(1).next_power_of_two() - 1;
 
         Self {
             rom,
             ram,
-            rom_bank: 1,
-            ram_bank: 0,
+            battery,
+            multicart,
+            rom_bank_mask,
+            bank1: 1,
+            bank2: 0,
             ram_enabled: false,
             banking_mode_advanced: false,
         }
     }
+
+    /// Returns `true` if the cart was detected as an MBC1M multicart.
+    pub fn is_multicart(&self) -> bool {
+        self.multicart
+    }
+
+    /// Returns the ROM bank bits provided by `BANK2`.
+    fn upper_rom_bank(&self) -> usize {
+        let shift = if self.multicart { 4 } else { 5 };
+
+        (self.bank2 as usize) << shift
+    }
+
+    fn rom_bank(&self) -> usize {
+        let mask = if self.multicart { 0x0F } else { 0x1F };
+
+        self.upper_rom_bank() | (self.bank1 & mask) as usize
+    }
+
+    fn ram_address(&self, address: usize) -> Option<usize> {
+        if !self.ram_enabled || self.ram.is_empty() {
+            return None;
+        }
+
+        let bank = if self.banking_mode_advanced {
+            self.bank2
+        } else {
+            0
+        };
+
+        // Carts with less than 32 KiB of RAM ignore the bank, and smaller RAM is mirrored.
+        Some(map_ram_address(bank, address) % self.ram.len())
+    }
 }
 
 impl MemoryBankController for Mbc1 {
@@ -35,63 +92,163 @@ impl MemoryBankController for Mbc1 {
     }
 
     fn rom_read(&self, address: usize) -> u8 {
-        match address {
-            ROM0_START..=ROM0_END => *self.rom.get(address).unwrap_or(&0xFF),
-            ROM_BANK_START..=ROM_BANK_END => *self
-                .rom
-                .get(map_rom_address(self.rom_bank, address))
-                .unwrap_or(&0xFF),
+        let bank = match address {
+            ROM0_START..=ROM0_END if self.banking_mode_advanced => self.upper_rom_bank(),
+            ROM0_START..=ROM0_END => 0,
+            ROM_BANK_START..=ROM_BANK_END => self.rom_bank(),
             _ => panic!("ROM read out of range for MBC1: {:#X}", address),
-        }
+        };
+
+        let address = (bank & self.rom_bank_mask) * ROM_BANK_SIZE + (address % ROM_BANK_SIZE);
+
+        *self.rom.get(address).unwrap_or(&0xFF)
     }
 
     fn rom_write(&mut self, address: usize, value: u8) {
         match address {
-            0x0000..=0x1FFF => self.ram_enabled = value & 0x0A == 0x0A,
-            0x2000..=0x3FFF => {
-                self.rom_bank = 1.max(value & 0x1F);
-            }
-            0x4000..=0x5FFF => {
-                let bits = value & 0x03;
-
-                if self.banking_mode_advanced {
-                    // "Large RAM" carts allow RAM bank swapping in advanced mode
-                    if self.ram.len() >= EXTERNAL_RAM_SIZE {
-                        self.ram_bank = bits;
-                    } else {
-                        // "Large ROM" carts allow special case ROM banking in advanced mode that I
-                        // really do not understand at the moment...
-                        unimplemented!("Support for 'large ROM' carts is not yet complete");
-                    }
-                } else {
-                    self.rom_bank = self.rom_bank & 0x1F | (bits << 5);
-                }
-            }
+            0x0000..=0x1FFF => self.ram_enabled = value & 0x0F == 0x0A,
+            // Bank 0 is translated to bank 1 based on all 5 bits, even if the upper bits are
+            // masked off afterwards. This means banks $00, $20, $40 and $60 can't be mapped to
+            // $4000-$7FFF.
+            0x2000..=0x3FFF => self.bank1 = 1.max(value & 0x1F),
+            0x4000..=0x5FFF => self.bank2 = value & 0x03,
+            0x6000..=0x7FFF => self.banking_mode_advanced = value & 0x01 != 0,
             _ => panic!("ROM register write out of range for MBC1: {:#X}", address),
         };
     }
 
     fn ram_read(&self, address: usize) -> u8 {
-        if !self.ram_enabled {
-            return 0xFF;
+        match self.ram_address(address) {
+            Some(address) => self.ram[address],
+            None => 0xFF,
         }
-
-        *self
-            .ram
-            .get(map_ram_address(self.ram_bank, address))
-            .unwrap_or(&0xFF)
     }
 
     fn ram_write(&mut self, address: usize, value: u8) {
-        if !self.ram_enabled {
-            return;
+        if let Some(address) = self.ram_address(address) {
+            self.ram[address] = value;
+        }
+    }
+
+    fn has_battery(&self) -> bool {
+        self.battery
+    }
+
+    fn export_ram(&self) -> Vec<u8> {
+        self.ram.clone()
+    }
+
+    fn import_ram(&mut self, data: &[u8]) -> Result<(), ImportError> {
+        import_ram(&mut self.ram, data)
+    }
+}
+
+/// Returns `true` if `rom` looks like an MBC1M multicart. There's nothing in the header to say so,
+/// but multicarts are always 1 MiB, and the second game starts with its own header (including the
+/// logo) at bank $10.
+fn is_multicart(rom: &[u8]) -> bool {
+    rom.len() == 4 * MULTICART_GAME_SIZE
+        && rom[LOGO_START..=LOGO_END]
+            == rom[MULTICART_GAME_SIZE + LOGO_START..=MULTICART_GAME_SIZE + LOGO_END]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cartridge::constants::CONTROLLER_TYPE;
+
+    /// Builds a ROM with `banks` banks, each starting with its own bank number.
+    fn rom(banks: usize, ram_size: u8) -> Vec<u8> {
+        let mut rom = vec![0; banks * ROM_BANK_SIZE];
+
+        for bank in 0..banks {
+            rom[bank * ROM_BANK_SIZE] = bank as u8;
         }
 
-        let address = map_ram_address(self.ram_bank, address);
-        let slot = self.ram.get_mut(address);
+        rom[CONTROLLER_TYPE] = 0x03; // MBC1+RAM+BATTERY
+        rom[RAM_SIZE] = ram_size;
 
-        if let Some(slot) = slot {
-            *slot = value;
+        for (i, byte) in rom[LOGO_START..=LOGO_END].iter_mut().enumerate() {
+            *byte = i as u8 + 1;
         }
+
+        rom
+    }
+
+    #[test]
+    fn banking_modes() {
+        let mut controller = Mbc1::new(rom(128, 0));
+        assert!(!controller.is_multicart());
+        assert_eq!(controller.rom_read(0x4000), 1);
+
+        controller.rom_write(0x2000, 0x05);
+        assert_eq!(controller.rom_read(0x4000), 0x05);
+
+        // Only the low 5 bits are checked for bank 0.
+        controller.rom_write(0x2000, 0x20);
+        controller.rom_write(0x4000, 0x01);
+        assert_eq!(controller.rom_read(0x4000), 0x21);
+        assert_eq!(controller.rom_read(0x0000), 0x00);
+
+        controller.rom_write(0x6000, 0x01);
+        assert_eq!(controller.rom_read(0x0000), 0x20);
+        assert_eq!(controller.rom_read(0x4000), 0x21);
+    }
+
+    #[test]
+    fn masks_banks_by_rom_size() {
+        let mut controller = Mbc1::new(rom(16, 0));
+
+        controller.rom_write(0x2000, 0x12);
+        assert_eq!(controller.rom_read(0x4000), 0x02);
+
+        // Bank 2 doesn't exist on small carts, so mode 1 doesn't remap $0000-$3FFF.
+        controller.rom_write(0x4000, 0x03);
+        controller.rom_write(0x6000, 0x01);
+        assert_eq!(controller.rom_read(0x0000), 0x00);
+        assert_eq!(controller.rom_read(0x4000), 0x02);
+    }
+
+    #[test]
+    fn ram_banks_in_mode_1() {
+        let mut controller = Mbc1::new(rom(4, 0x03)); // 32 KiB
+        controller.rom_write(0x0000, 0x0A);
+        controller.ram_write(0xA000, 1);
+
+        controller.rom_write(0x4000, 0x02);
+        controller.ram_write(0xA000, 2);
+        assert_eq!(controller.ram_read(0xA000), 2);
+
+        controller.rom_write(0x6000, 0x01);
+        assert_eq!(controller.ram_read(0xA000), 0);
+        controller.ram_write(0xA000, 3);
+
+        controller.rom_write(0x6000, 0x00);
+        assert_eq!(controller.ram_read(0xA000), 2);
+        assert_eq!(controller.export_ram()[0x4000], 3);
+
+        controller.rom_write(0x0000, 0x0B);
+        assert_eq!(controller.ram_read(0xA000), 0xFF);
+    }
+
+    #[test]
+    fn multicart() {
+        let mut rom = rom(64, 0);
+        rom.copy_within(LOGO_START..=LOGO_END, MULTICART_GAME_SIZE + LOGO_START);
+
+        let mut controller = Mbc1::new(rom);
+        assert!(controller.is_multicart());
+
+        controller.rom_write(0x2000, 0x13);
+        controller.rom_write(0x4000, 0x01);
+        assert_eq!(controller.rom_read(0x4000), 0x13);
+        assert_eq!(controller.rom_read(0x0000), 0x00);
+
+        controller.rom_write(0x6000, 0x01);
+        assert_eq!(controller.rom_read(0x0000), 0x10);
+
+        controller.rom_write(0x4000, 0x03);
+        assert_eq!(controller.rom_read(0x0000), 0x30);
+        assert_eq!(controller.rom_read(0x4000), 0x33);
     }
 }
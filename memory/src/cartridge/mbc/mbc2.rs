@@ -0,0 +1,169 @@
+use super::ControllerType;
+use crate::cartridge::mbc::{has_battery, import_ram, ImportError, MemoryBankController};
+use crate::constants::{
+    EXTERNAL_RAM_START, ROM0_END, ROM0_START, ROM_BANK_END, ROM_BANK_SIZE, ROM_BANK_START,
+};
+
+/// The number of 4-bit cells of RAM built into the MBC2.
+const RAM_SIZE: usize = 512;
+
+/// MBC2, which supports up to 256 KiB of ROM, and has 512 4-bit cells of RAM built in.
+///
+/// Both of its registers are mapped to `$0000-$3FFF`, and bit 8 of the address picks which one
+/// is written: the RAM enable register when it's clear, and the ROM bank register when it's set.
+///
+/// Only the lower 4 bits of each RAM cell exist, so the upper 4 bits always read as 1s. The 512
+/// cells are mirrored across the whole of `$A000-$BFFF`.
+pub struct Mbc2 {
+    rom: Vec<u8>,
+
+    /// One byte per cell, with the upper 4 bits always clear.
+    ram: Vec<u8>,
+    battery: bool,
+
+    /// The ROM bank bits that exist on the cart. Banks past the end of the ROM wrap around.
+    rom_bank_mask: usize,
+    rom_bank: u8,
+    ram_enabled: bool,
+}
+
+impl Mbc2 {
+    pub fn new(rom: Vec<u8>) -> Self {
+        let battery = has_battery(&rom);
+        let rom_bank_mask = (rom.len() / ROM_BANK_SIZE).max(1).next_power_of_two() - 1;
+
+        Self {
+            rom,
+            ram: vec![0; RAM_SIZE],
+            battery,
+            rom_bank_mask,
+            rom_bank: 1,
+            ram_enabled: false,
+        }
+    }
+
+    fn ram_address(&self, address: usize) -> Option<usize> {
+        if !self.ram_enabled {
+            return None;
+        }
+
+        Some((address - EXTERNAL_RAM_START) % RAM_SIZE)
+    }
+}
+
+impl MemoryBankController for Mbc2 {
+    fn get_controller_type(&self) -> ControllerType {
+        ControllerType::Mbc2
+    }
+
+    fn rom_read(&self, address: usize) -> u8 {
+        let bank = match address {
+            ROM0_START..=ROM0_END => 0,
+            ROM_BANK_START..=ROM_BANK_END => self.rom_bank as usize & self.rom_bank_mask,
+            _ => panic!("ROM read out of range for MBC2: {:#X}", address),
+        };
+
+        *self
+            .rom
+            .get(bank * ROM_BANK_SIZE + (address % ROM_BANK_SIZE))
+            .unwrap_or(&0xFF)
+    }
+
+    fn rom_write(&mut self, address: usize, value: u8) {
+        match address {
+            0x0000..=0x3FFF if address & 0x0100 == 0 => {
+                self.ram_enabled = value & 0x0F == 0x0A;
+            }
+            0x0000..=0x3FFF => self.rom_bank = 1.max(value & 0x0F),
+            _ => (),
+        }
+    }
+
+    fn ram_read(&self, address: usize) -> u8 {
+        match self.ram_address(address) {
+            Some(address) => 0xF0 | self.ram[address],
+            None => 0xFF,
+        }
+    }
+
+    fn ram_write(&mut self, address: usize, value: u8) {
+        if let Some(address) = self.ram_address(address) {
+            self.ram[address] = value & 0x0F;
+        }
+    }
+
+    fn has_battery(&self) -> bool {
+        self.battery
+    }
+
+    /// Each cell is saved as a byte, in the low 4 bits.
+    fn export_ram(&self) -> Vec<u8> {
+        self.ram.clone()
+    }
+
+    fn import_ram(&mut self, data: &[u8]) -> Result<(), ImportError> {
+        import_ram(&mut self.ram, data)?;
+
+        for cell in &mut self.ram {
+            *cell &= 0x0F;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selects_registers_by_address_bit_8() {
+        let mut rom = vec![0; 32 * ROM_BANK_SIZE];
+
+        for bank in 0..32 {
+            rom[bank * ROM_BANK_SIZE] = bank as u8;
+        }
+
+        let mut controller = Mbc2::new(rom);
+        assert_eq!(controller.rom_read(0x4000), 1);
+
+        // Bit 8 is clear, so this enables RAM rather than switching banks.
+        controller.rom_write(0x3000, 0x0A);
+        assert_eq!(controller.rom_read(0x4000), 1);
+
+        controller.rom_write(0x2100, 0x00);
+        assert_eq!(controller.rom_read(0x4000), 1);
+
+        // Only 4 bits of the bank number are used.
+        controller.rom_write(0x0100, 0x1F);
+        assert_eq!(controller.rom_read(0x4000), 0x0F);
+        assert_eq!(controller.rom_read(0x0000), 0x00);
+    }
+
+    #[test]
+    fn half_byte_ram() {
+        let mut controller = Mbc2::new(vec![0; 2 * ROM_BANK_SIZE]);
+        assert_eq!(controller.ram_read(0xA000), 0xFF);
+
+        controller.rom_write(0x0000, 0x0A);
+        controller.ram_write(0xA001, 0x5C);
+        assert_eq!(controller.ram_read(0xA001), 0xFC);
+
+        // The 512 cells are mirrored across the whole RAM area.
+        assert_eq!(controller.ram_read(0xA201), 0xFC);
+        assert_eq!(controller.ram_read(0xBE01), 0xFC);
+
+        let data = controller.export_ram();
+        assert_eq!(data.len(), RAM_SIZE);
+        assert_eq!(data[1], 0x0C);
+
+        let mut controller = Mbc2::new(vec![0; 2 * ROM_BANK_SIZE]);
+        controller.import_ram(&[0xFF; RAM_SIZE]).unwrap();
+        controller.rom_write(0x0000, 0x0A);
+        assert_eq!(controller.ram_read(0xA000), 0xFF);
+        assert_eq!(controller.export_ram()[0], 0x0F);
+
+        controller.rom_write(0x0000, 0x00);
+        assert_eq!(controller.ram_read(0xA000), 0xFF);
+    }
+}
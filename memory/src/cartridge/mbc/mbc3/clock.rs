@@ -0,0 +1,153 @@
+use gb_rs_common::clock::{Speed, T_CYCLES_PER_M_CYCLE, T_CYCLES_PER_SECOND};
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::Instant;
+
+/// The frequency of the RTC's crystal oscillator, which is independent of the CPU clock.
+pub const TICKS_PER_SECOND: u64 = 32_768;
+
+/// The number of normal speed T-cycles per RTC tick.
+const T_CYCLES_PER_TICK: u64 = T_CYCLES_PER_SECOND / TICKS_PER_SECOND;
+
+/// Tells an [`Rtc`](super::rtc::Rtc) how much time has passed.
+pub trait ClockSource: Send {
+    /// Returns the number of RTC ticks ([`TICKS_PER_SECOND`]) that have passed since the previous
+    /// call.
+    fn take_ticks(&mut self) -> u64;
+
+    /// Advances the clock by `m_cycles` M-cycles executed by the CPU at the given speed. Clocks
+    /// that don't follow emulated time ignore this.
+    fn advance(&mut self, _m_cycles: u64, _speed: Speed) {}
+}
+
+/// Follows the host's wall clock, regardless of how fast the emulator is running.
+pub struct HostClock {
+    start: Instant,
+    taken: u64,
+}
+
+impl HostClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            taken: 0,
+        }
+    }
+}
+
+impl Default for HostClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClockSource for HostClock {
+    fn take_ticks(&mut self) -> u64 {
+        // Counting from a fixed start means no fractions of a tick are lost between calls.
+        let total = self.start.elapsed().as_nanos() * TICKS_PER_SECOND as u128 / 1_000_000_000;
+        let ticks = total as u64 - self.taken;
+        self.taken = total as u64;
+
+        ticks
+    }
+}
+
+/// Follows emulated time, as measured by the cycles executed by the CPU. This keeps the RTC in
+/// step with fast-forwarding, pausing and replays.
+#[derive(Default)]
+pub struct EmulatedClock {
+    /// Normal speed T-cycles that haven't added up to a whole tick yet.
+    t_cycles: u64,
+}
+
+impl EmulatedClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ClockSource for EmulatedClock {
+    fn take_ticks(&mut self) -> u64 {
+        let ticks = self.t_cycles / T_CYCLES_PER_TICK;
+        self.t_cycles %= T_CYCLES_PER_TICK;
+
+        ticks
+    }
+
+    fn advance(&mut self, m_cycles: u64, speed: Speed) {
+        // The oscillator doesn't speed up along with the CPU.
+        self.t_cycles += match speed {
+            Speed::Normal => m_cycles * T_CYCLES_PER_M_CYCLE,
+            Speed::Double => m_cycles * T_CYCLES_PER_M_CYCLE / 2,
+        };
+    }
+}
+
+/// The kinds of [`ClockSource`] that can drive a cartridge's RTC.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RtcClock {
+    /// See [`EmulatedClock`].
+    #[default]
+    Emulated,
+
+    /// See [`HostClock`].
+    Host,
+}
+
+impl RtcClock {
+    pub fn create(self) -> Box<dyn ClockSource> {
+        match self {
+            Self::Emulated => Box::new(EmulatedClock::new()),
+            Self::Host => Box::new(HostClock::new()),
+        }
+    }
+}
+
+impl FromStr for RtcClock {
+    type Err = ParseRtcClockError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "emulated" => Ok(Self::Emulated),
+            "host" => Ok(Self::Host),
+            _ => Err(ParseRtcClockError(s.to_owned())),
+        }
+    }
+}
+
+impl Display for RtcClock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Emulated => "emulated",
+            Self::Host => "host",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unknown RTC clock {0:?}, expected emulated or host")]
+pub struct ParseRtcClockError(String);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emulated_clock_ignores_double_speed() {
+        let mut clock = EmulatedClock::new();
+        let m_cycles_per_second = T_CYCLES_PER_SECOND / T_CYCLES_PER_M_CYCLE;
+
+        clock.advance(m_cycles_per_second, Speed::Normal);
+        assert_eq!(clock.take_ticks(), TICKS_PER_SECOND);
+        assert_eq!(clock.take_ticks(), 0);
+
+        clock.advance(m_cycles_per_second, Speed::Double);
+        assert_eq!(clock.take_ticks(), TICKS_PER_SECOND / 2);
+
+        // Fractions of a tick carry over to the next call.
+        clock.advance(31, Speed::Normal);
+        assert_eq!(clock.take_ticks(), 0);
+        clock.advance(1, Speed::Normal);
+        assert_eq!(clock.take_ticks(), 1);
+    }
+}
@@ -1,9 +1,13 @@
 use super::ControllerType;
-use crate::cartridge::constants::RAM_SIZE;
-use crate::cartridge::mbc::{map_ram_address, map_rom_address};
+use crate::cartridge::constants::{CONTROLLER_TYPE, RAM_SIZE};
+use crate::cartridge::mbc::{
+    has_battery, import_ram, map_ram_address, map_rom_address, ImportError,
+};
 use crate::cartridge::{get_ram_size, MemoryBankController};
 use crate::constants::{ROM0_END, ROM0_START, ROM_BANK_END, ROM_BANK_START};
+use gb_rs_common::clock::Speed;
 
+pub mod clock;
 pub mod rtc;
 
 pub enum RamMode {
@@ -14,24 +18,33 @@ pub enum RamMode {
 pub struct Mbc3 {
     rom: Vec<u8>,
     ram: Vec<u8>,
+    battery: bool,
     rom_bank: u8,
     ram_rtc_enabled: bool,
     ram_mode: RamMode,
-    rtc: rtc::Rtc,
+
+    /// The real time clock, if the cart has one.
+    rtc: Option<rtc::Rtc>,
 }
 
 impl Mbc3 {
     pub fn new(rom: Vec<u8>) -> Self {
         let ram_size = get_ram_size(rom[RAM_SIZE]).expect("Unsupported RAM size");
         let ram = vec![0; ram_size];
+        let battery = has_battery(&rom);
+        let rtc = match rom[CONTROLLER_TYPE] {
+            0x0F | 0x10 => Some(rtc::Rtc::new()),
+            _ => None,
+        };
 
         Self {
             rom,
             ram,
+            battery,
             rom_bank: 1,
             ram_rtc_enabled: false,
             ram_mode: RamMode::Normal(0),
-            rtc: rtc::Rtc::new(),
+            rtc,
         }
     }
 }
@@ -63,7 +76,11 @@ impl MemoryBankController for Mbc3 {
                     self.ram_mode = RamMode::Rtc(value - 8);
                 }
             }
-            0x6000..=0x7FFF => self.rtc.latch_write(value),
+            0x6000..=0x7FFF => {
+                if let Some(rtc) = &mut self.rtc {
+                    rtc.latch_write(value);
+                }
+            }
             _ => (),
         };
     }
@@ -78,7 +95,10 @@ impl MemoryBankController for Mbc3 {
                 .ram
                 .get(map_ram_address(bank, address))
                 .unwrap_or(&0xFF),
-            RamMode::Rtc(register) => self.rtc.register_read(register),
+            RamMode::Rtc(register) => match &self.rtc {
+                Some(rtc) => rtc.register_read(register),
+                None => 0xFF,
+            },
         }
     }
 
@@ -95,7 +115,115 @@ impl MemoryBankController for Mbc3 {
                     *slot = value;
                 }
             }
-            RamMode::Rtc(register) => self.rtc.register_write(register, value),
+            RamMode::Rtc(register) => {
+                if let Some(rtc) = &mut self.rtc {
+                    rtc.register_write(register, value);
+                }
+            }
         };
     }
+
+    fn tick(&mut self, m_cycles: u8, speed: Speed) {
+        if let Some(rtc) = &mut self.rtc {
+            rtc.tick(m_cycles.into(), speed);
+        }
+    }
+
+    fn rtc(&self) -> Option<&rtc::Rtc> {
+        self.rtc.as_ref()
+    }
+
+    fn rtc_mut(&mut self) -> Option<&mut rtc::Rtc> {
+        self.rtc.as_mut()
+    }
+
+    fn has_battery(&self) -> bool {
+        self.battery
+    }
+
+    /// Carts with an RTC have its state appended to the RAM, in the [`rtc::FOOTER_SIZE`] format.
+    fn export_ram(&self) -> Vec<u8> {
+        let mut data = self.ram.clone();
+
+        if let Some(rtc) = &self.rtc {
+            data.extend_from_slice(&rtc.export());
+        }
+
+        data
+    }
+
+    /// The RTC's footer changes every second, so only the number of times its registers have
+    /// been written is included.
+    fn ram_snapshot(&self) -> Vec<u8> {
+        let mut data = self.ram.clone();
+
+        if let Some(rtc) = &self.rtc {
+            data.extend_from_slice(&rtc.edits().to_le_bytes());
+        }
+
+        data
+    }
+
+    /// Saves from emulators that don't support the RTC won't have a footer, in which case the RTC
+    /// is left as it is.
+    fn import_ram(&mut self, data: &[u8]) -> Result<(), ImportError> {
+        let ram_size = self.ram.len();
+
+        if let Some(rtc) = &mut self.rtc {
+            if data.len() > ram_size {
+                let (ram, footer) = data.split_at(ram_size);
+
+                if !rtc.import(footer) {
+                    return Err(ImportError::Size {
+                        expected: ram_size + rtc::FOOTER_SIZE,
+                        actual: data.len(),
+                    });
+                }
+
+                return import_ram(&mut self.ram, ram);
+            }
+        }
+
+        import_ram(&mut self.ram, data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saves_rtc_footer() {
+        let mut rom = vec![0; 0x8000];
+        rom[CONTROLLER_TYPE] = 0x10; // MBC3+TIMER+RAM+BATTERY
+        rom[RAM_SIZE] = 0x02; // 8 KiB
+
+        let mut controller = Mbc3::new(rom.clone());
+        controller.rom_write(0x0000, 0x0A);
+        controller.ram_write(0xA000, 13);
+        controller.rom_write(0x4000, 0x0A); // Hours
+        controller.ram_write(0xA000, 5);
+
+        let data = controller.export_ram();
+        assert_eq!(data.len(), 0x2000 + rtc::FOOTER_SIZE);
+
+        let mut controller = Mbc3::new(rom.clone());
+        controller.rom_write(0x0000, 0x0A);
+        controller.import_ram(&data).unwrap();
+        assert_eq!(controller.ram_read(0xA000), 13);
+
+        controller.rom_write(0x4000, 0x0A);
+        assert_eq!(controller.ram_read(0xA000), 5);
+
+        // Saves without the footer are still accepted.
+        controller.import_ram(&data[..0x2000]).unwrap();
+        controller
+            .import_ram(&data[..0x2000 + rtc::LEGACY_FOOTER_SIZE])
+            .unwrap();
+        assert!(controller.import_ram(&data[..0x2010]).is_err());
+
+        // Carts without an RTC don't have a footer at all.
+        rom[CONTROLLER_TYPE] = 0x13; // MBC3+RAM+BATTERY
+        assert_eq!(Mbc3::new(rom).export_ram().len(), 0x2000);
+    }
 }
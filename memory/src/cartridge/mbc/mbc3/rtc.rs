@@ -1,6 +1,9 @@
+use super::clock::{ClockSource, EmulatedClock, TICKS_PER_SECOND};
 use gb_rs_common::bytes::{bytes_to_word, word_to_bytes};
-use std::cell::RefCell;
-use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use gb_rs_common::clock::Speed;
+use std::cell::{Cell, RefCell};
+use std::convert::TryInto;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 const RTC_REGISTER_SECS: usize = 0;
 const RTC_REGISTER_MINS: usize = 1;
@@ -8,6 +11,16 @@ const RTC_REGISTER_HOURS: usize = 2;
 const RTC_REGISTER_DAYS_LOW: usize = 3;
 const RTC_REGISTER_DAYS_HIGH: usize = 4;
 
+/// The size of the RTC state that's appended to `.sav` files, in the format used by BGB, VBA-M
+/// and SameBoy: the 5 time registers, then the 5 latched registers, each as a little-endian
+/// 32-bit value, followed by the Unix timestamp the state was saved at as a little-endian 64-bit
+/// value.
+pub const FOOTER_SIZE: usize = 48;
+
+/// The size of the older variant of the footer, with a 32-bit timestamp.
+pub const LEGACY_FOOTER_SIZE: usize = 44;
+
+#[derive(Clone, Copy)]
 struct RtcInner {
     seconds: u8,
     minutes: u8,
@@ -36,13 +49,52 @@ impl RtcInner {
             days_high: parts[RTC_REGISTER_DAYS_HIGH],
         }
     }
+
+    pub fn parts(&self) -> [u8; 5] {
+        [
+            self.seconds,
+            self.minutes,
+            self.hours,
+            self.days_low,
+            self.days_high,
+        ]
+    }
+}
+
+/// The time kept by an [`Rtc`]: a day counter, and the time of day.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RtcTime {
+    /// The day counter, which wraps around after day 511.
+    pub days: u16,
+    pub hours: u8,
+    pub minutes: u8,
+    pub seconds: u8,
+}
+
+impl RtcTime {
+    fn total_seconds(&self) -> u64 {
+        self.days as u64 * 86_400
+            + self.hours as u64 * 3600
+            + self.minutes as u64 * 60
+            + self.seconds as u64
+    }
 }
 
 pub struct Rtc {
     latch_prev: u8,
-    latched: bool,
+
+    /// A snapshot of the time registers, taken when the RTC was last latched. This is what the
+    /// game reads back.
+    latched: RtcInner,
     inner: RefCell<RtcInner>,
-    timer: RefCell<Option<Instant>>,
+
+    /// The number of ticks counted towards the next second.
+    subsecond: Cell<u64>,
+    clock: RefCell<Box<dyn ClockSource>>,
+
+    /// The number of times the registers have been changed by something other than the passing of
+    /// time, such as the game writing to them.
+    edits: u64,
 }
 
 impl Default for Rtc {
@@ -56,13 +108,20 @@ const RTC_FLAG_CARRY: u8 = 0x80;
 const RTC_FLAGS: u8 = RTC_FLAG_HALT | RTC_FLAG_CARRY;
 
 impl Rtc {
-    /// Creates a new [`Rtc`] with default contents.
+    /// Creates a new [`Rtc`] with default contents, driven by an [`EmulatedClock`].
     pub fn new() -> Self {
+        Self::with_clock(Box::new(EmulatedClock::new()))
+    }
+
+    /// Creates a new [`Rtc`] with default contents, driven by `clock`.
+    pub fn with_clock(clock: Box<dyn ClockSource>) -> Self {
         Self {
             latch_prev: 0xFF,
-            latched: false,
+            latched: RtcInner::new(),
             inner: RefCell::new(RtcInner::new()),
-            timer: RefCell::new(Some(Instant::now())),
+            subsecond: Cell::new(0),
+            clock: RefCell::new(clock),
+            edits: 0,
         }
     }
 
@@ -72,33 +131,69 @@ impl Rtc {
     /// from the moment it was saved). `time_parts` is an array of RTC registers to initialize the
     /// [`Rtc`] with, in the order `[secs, mins, hours, days_low, days_high]`.
     pub fn load(last_timestamp: u64, time_parts: [u8; 5]) -> Self {
-        let rtc = Self {
-            latch_prev: 0xFF,
-            latched: false,
-            inner: RefCell::new(RtcInner::load(time_parts)),
-            timer: RefCell::new(Some(Instant::now())),
+        let mut rtc = Self::new();
+        rtc.restore(last_timestamp, time_parts, time_parts);
+
+        rtc
+    }
+
+    /// Restores the RTC's state from the footer of a `.sav` file, in either the [`FOOTER_SIZE`]
+    /// or [`LEGACY_FOOTER_SIZE`] format. Returns `false`, leaving the RTC as it was, if the footer
+    /// is neither size.
+    ///
+    /// Any time that passed since the footer was saved is added to the time registers.
+    pub fn import(&mut self, footer: &[u8]) -> bool {
+        let timestamp = match footer.len() {
+            FOOTER_SIZE => u64::from_le_bytes(footer[40..48].try_into().unwrap()),
+            LEGACY_FOOTER_SIZE => u32::from_le_bytes(footer[40..44].try_into().unwrap()) as u64,
+            _ => return false,
+        };
+
+        // Only the low byte of each 32-bit register is meaningful.
+        let parts = |offset: usize| {
+            let mut parts = [0; 5];
+
+            for (i, part) in parts.iter_mut().enumerate() {
+                *part = footer[offset + i * 4];
+            }
+
+            parts[RTC_REGISTER_SECS] &= 0x3F;
+            parts[RTC_REGISTER_MINS] &= 0x3F;
+            parts[RTC_REGISTER_HOURS] &= 0x1F;
+            parts[RTC_REGISTER_DAYS_HIGH] &= RTC_FLAGS | 1;
+            parts
         };
 
-        let unix_now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("System time is before Unix epoch")
-            .as_secs();
+        self.restore(timestamp, parts(0), parts(20));
+
+        true
+    }
 
-        if last_timestamp > unix_now {
-            panic!("Loaded timestamp is greater than the current system time and everything is broken.");
+    /// Returns the RTC's state in the [`FOOTER_SIZE`] format, timestamped with the current time.
+    pub fn export(&self) -> [u8; FOOTER_SIZE] {
+        let mut footer = [0; FOOTER_SIZE];
+        let parts = self.get_time_parts();
+        let latched = self.get_latched_parts();
+
+        for (i, part) in parts.iter().chain(latched.iter()).enumerate() {
+            footer[i * 4] = *part;
         }
 
-        rtc.update_from_elapsed_secs(unix_now - last_timestamp);
+        footer[40..].copy_from_slice(&unix_now().to_le_bytes());
 
-        rtc
+        footer
     }
 
-    /// Returns `true` if the RTC has been latched.
-    ///
-    /// A "latched" RTC freezes the values of its time registers, but will continue to count time.
-    /// Once an RTC is "unlatched", any time that passed is added to the registers.
-    pub fn is_latched(&self) -> bool {
-        self.latched
+    /// Replaces the source of time that drives the RTC.
+    pub fn set_clock(&mut self, clock: Box<dyn ClockSource>) {
+        self.refresh();
+        self.clock.replace(clock);
+    }
+
+    /// Advances the RTC's clock by `m_cycles` M-cycles executed by the CPU at the given speed.
+    /// This is ignored unless it's driven by an [`EmulatedClock`].
+    pub fn tick(&mut self, m_cycles: u64, speed: Speed) {
+        self.clock.get_mut().advance(m_cycles, speed);
     }
 
     /// Returns `true` if the RTC has been halted.
@@ -106,31 +201,103 @@ impl Rtc {
     /// A "halted" RTC stops counting time until it's "un-halted". Any time that passes while the
     /// RTC has been halted is ignored.
     pub fn is_halted(&self) -> bool {
-        self.timer.borrow().is_none()
+        self.inner.borrow().days_high & RTC_FLAG_HALT != 0
+    }
+
+    /// Returns the current time.
+    pub fn time(&self) -> RtcTime {
+        let [seconds, minutes, hours, days_low, days_high] = self.get_time_parts();
+
+        RtcTime {
+            days: bytes_to_word(days_high & 1, days_low),
+            hours,
+            minutes,
+            seconds,
+        }
+    }
+
+    /// Sets the current time, which the game sees the next time it latches the RTC.
+    ///
+    /// Values past the end of their range carry over, e.g. 90 minutes is 1 hour and 30 minutes,
+    /// and days past 511 wrap around and set the day carry flag. The flags are otherwise left as
+    /// they are.
+    pub fn set_time(&mut self, time: RtcTime) {
+        self.refresh();
+        self.subsecond.set(0);
+        self.edits += 1;
+
+        let flags = self.inner.borrow().days_high & RTC_FLAGS;
+        self.inner.replace(RtcInner::load([0, 0, 0, 0, flags]));
+        self.update_from_elapsed_secs(time.total_seconds());
+    }
+
+    /// Moves the current time forward by `duration`, as if it had passed while the game was
+    /// running. This is ignored while the RTC is halted.
+    pub fn advance(&mut self, duration: Duration) {
+        self.refresh();
+
+        if self.is_halted() {
+            return;
+        }
+
+        self.edits += 1;
+
+        let ticks = self.subsecond.get()
+            + duration.subsec_nanos() as u64 * TICKS_PER_SECOND / 1_000_000_000;
+
+        self.subsecond.set(ticks % TICKS_PER_SECOND);
+        self.update_from_elapsed_secs(duration.as_secs() + ticks / TICKS_PER_SECOND);
+    }
+
+    /// Returns `true` if the day counter has overflowed since the carry flag was last cleared.
+    pub fn has_day_carry(&self) -> bool {
+        self.get_time_parts()[RTC_REGISTER_DAYS_HIGH] & RTC_FLAG_CARRY != 0
+    }
+
+    /// Sets or clears the day counter's carry flag.
+    pub fn set_day_carry(&mut self, carry: bool) {
+        self.refresh();
+        self.edits += 1;
+
+        let mut inner = self.inner.borrow_mut();
+
+        if carry {
+            inner.days_high |= RTC_FLAG_CARRY;
+        } else {
+            inner.days_high &= !RTC_FLAG_CARRY;
+        }
+    }
+
+    /// Returns the number of times the registers have been changed by something other than the
+    /// passing of time: the game writing to them, or [`set_time`](Self::set_time),
+    /// [`advance`](Self::advance) and [`set_day_carry`](Self::set_day_carry). This stays the same
+    /// while the RTC is just counting, so it can be used to tell whether its state needs saving.
+    pub fn edits(&self) -> u64 {
+        self.edits
     }
 
     /// Returns the RTC's current time parts, in the order `[secs, mins, hours, days_low,
     /// days_high]`.
     pub fn get_time_parts(&self) -> [u8; 5] {
         self.refresh();
+        self.inner.borrow().parts()
+    }
 
-        let inner = self.inner.borrow();
-
-        [
-            inner.seconds,
-            inner.minutes,
-            inner.hours,
-            inner.days_low,
-            inner.days_high,
-        ]
+    /// Returns the time parts as of when the RTC was last latched, in the same order as
+    /// [`get_time_parts`](Self::get_time_parts).
+    pub fn get_latched_parts(&self) -> [u8; 5] {
+        self.latched.parts()
     }
 
     /// Writes a value to the RTC's latch register.
     ///
-    /// The RTC's latch is toggled by writing `0x0` followed by `0x1`.
+    /// Writing `0x0` followed by `0x1` copies the current time into the latched registers, which
+    /// are what [`register_read`](Self::register_read) returns. The RTC keeps counting time
+    /// either way.
     pub fn latch_write(&mut self, value: u8) {
         if self.latch_prev == 0 && value == 1 {
-            self.latched = !self.latched;
+            self.refresh();
+            self.latched = *self.inner.borrow();
         }
 
         self.latch_prev = value;
@@ -141,58 +308,83 @@ impl Rtc {
     /// The `register` should be one of the registers named by the `RTC_REGISTER_*` constants.
     pub fn register_write(&mut self, register: u8, value: u8) {
         self.refresh();
+        self.edits += 1;
 
         let mut inner = self.inner.borrow_mut();
+        let latched = &mut self.latched;
 
+        // The new value can be read back straight away without latching again, so it's written to
+        // the latched register too. The other latched registers are left as they were.
         match register as usize {
-            RTC_REGISTER_SECS => inner.seconds = value & 0x3B,
-            RTC_REGISTER_MINS => inner.minutes = value & 0x3B,
-            RTC_REGISTER_HOURS => inner.hours = value & 0x17,
-            RTC_REGISTER_DAYS_LOW => inner.days_low = value,
+            RTC_REGISTER_SECS => {
+                inner.seconds = value & 0x3F;
+                latched.seconds = inner.seconds;
+
+                // Writing the seconds resets the counter that divides the oscillator down to 1 Hz.
+                self.subsecond.set(0);
+            }
+            RTC_REGISTER_MINS => {
+                inner.minutes = value & 0x3F;
+                latched.minutes = inner.minutes;
+            }
+            RTC_REGISTER_HOURS => {
+                inner.hours = value & 0x1F;
+                latched.hours = inner.hours;
+            }
+            RTC_REGISTER_DAYS_LOW => {
+                inner.days_low = value;
+                latched.days_low = inner.days_low;
+            }
             RTC_REGISTER_DAYS_HIGH => {
                 inner.days_high = (value & RTC_FLAGS) | (value & 1);
-
-                if inner.days_high & RTC_FLAG_HALT != 0 {
-                    self.timer.borrow_mut().take();
-                } else if self.is_halted() {
-                    self.timer.borrow_mut().replace(Instant::now());
-                }
+                latched.days_high = inner.days_high;
             }
             _ => (),
         };
     }
 
-    /// Reads a value from one of the RTC's time registers.
+    /// Reads a value from one of the RTC's latched time registers.
     ///
     /// The `register` should be one of the registers named by the `RTC_REGISTER_*` constants.
     pub fn register_read(&self, register: u8) -> u8 {
-        self.refresh();
-
-        let inner = self.inner.borrow();
+        let latched = &self.latched;
 
         match register as usize {
-            RTC_REGISTER_SECS => inner.seconds,
-            RTC_REGISTER_MINS => inner.minutes,
-            RTC_REGISTER_HOURS => inner.hours,
-            RTC_REGISTER_DAYS_LOW => inner.days_low,
-            RTC_REGISTER_DAYS_HIGH => inner.days_high,
+            RTC_REGISTER_SECS => latched.seconds,
+            RTC_REGISTER_MINS => latched.minutes,
+            RTC_REGISTER_HOURS => latched.hours,
+            RTC_REGISTER_DAYS_LOW => latched.days_low,
+            RTC_REGISTER_DAYS_HIGH => latched.days_high,
             _ => 0xFF,
         }
     }
 
-    fn refresh(&self) -> bool {
-        if !self.is_latched() {
-            let mut timer = self.timer.borrow_mut();
+    fn restore(&mut self, last_timestamp: u64, time_parts: [u8; 5], latched_parts: [u8; 5]) {
+        self.inner.replace(RtcInner::load(time_parts));
+        self.latched = RtcInner::load(latched_parts);
+        self.subsecond.set(0);
 
-            if let Some(t) = *timer {
-                self.update_from_elapsed_secs(t.elapsed().as_secs());
-                timer.replace(Instant::now());
+        // Time that passed before the state was restored doesn't count.
+        self.clock.get_mut().take_ticks();
 
-                return true;
-            }
+        // A save made on a machine whose clock is ahead of this one's just doesn't advance.
+        if !self.is_halted() {
+            self.update_from_elapsed_secs(unix_now().saturating_sub(last_timestamp));
+        }
+    }
+
+    /// Adds the time that passed since the last refresh to the time registers. Time that passed
+    /// while halted is thrown away.
+    fn refresh(&self) {
+        let ticks = self.clock.borrow_mut().take_ticks();
+
+        if self.is_halted() {
+            return;
         }
 
-        false
+        let ticks = self.subsecond.get() + ticks;
+        self.subsecond.set(ticks % TICKS_PER_SECOND);
+        self.update_from_elapsed_secs(ticks / TICKS_PER_SECOND);
     }
 
     fn update_from_elapsed_secs(&self, elapsed: u64) {
@@ -234,7 +426,8 @@ impl Rtc {
         }
 
         if elapsed >= 86_400 || carry.is_some() {
-            let days = bytes_to_word(inner.days_high, inner.days_low) as u64;
+            // Only bit 0 of the high register is part of the day counter, the rest are flags.
+            let days = bytes_to_word(inner.days_high & 1, inner.days_low) as u64;
             let days = days + carry.unwrap_or(0);
             let [high, low] = word_to_bytes((days % 512) as u16);
 
@@ -256,10 +449,19 @@ impl Rtc {
     }
 }
 
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System time is before Unix epoch")
+        .as_secs()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::time::Duration;
+    use gb_rs_common::clock::{T_CYCLES_PER_M_CYCLE, T_CYCLES_PER_SECOND};
+
+    const M_CYCLES_PER_SECOND: u64 = T_CYCLES_PER_SECOND / T_CYCLES_PER_M_CYCLE;
 
     macro_rules! assert_time {
         ( $rtc:expr, $expected:expr ) => {
@@ -271,20 +473,27 @@ mod tests {
     }
 
     #[test]
-    fn sleep_progress() {
-        let rtc = Rtc::new();
+    fn progress() {
+        let mut rtc = Rtc::new();
         assert_time!(rtc, [0, 0, 0, 0, 0]);
 
-        std::thread::sleep(Duration::new(2, 0));
+        rtc.tick(2 * M_CYCLES_PER_SECOND, Speed::Normal);
         assert_time!(rtc, [2, 0, 0, 0, 0]);
+
+        // Fractions of a second aren't lost when the time is read in between.
+        rtc.tick(M_CYCLES_PER_SECOND / 2, Speed::Normal);
+        assert_time!(rtc, [2, 0, 0, 0, 0]);
+        rtc.tick(M_CYCLES_PER_SECOND / 2, Speed::Normal);
+        assert_time!(rtc, [3, 0, 0, 0, 0]);
+
+        // The oscillator runs at the same rate in double speed mode.
+        rtc.tick(2 * M_CYCLES_PER_SECOND, Speed::Double);
+        assert_time!(rtc, [4, 0, 0, 0, 0]);
     }
 
     #[test]
     fn set_initial_state() {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        let now = unix_now();
 
         let rtc = Rtc::load(now - 4 * 3600, [0, 0, 0, 0, 0]);
         assert_time!(rtc, [0, 0, 4, 0, 0]);
@@ -318,21 +527,53 @@ mod tests {
     fn latch() {
         let mut rtc = Rtc::new();
 
-        assert!(!rtc.is_latched());
-        std::thread::sleep(Duration::new(1, 0));
+        rtc.tick(M_CYCLES_PER_SECOND, Speed::Normal);
         assert_time!(rtc, [1, 0, 0, 0, 0]);
+        assert_eq!(rtc.register_read(RTC_REGISTER_SECS as u8), 0);
 
         rtc.latch_write(0);
         rtc.latch_write(1);
-        assert!(rtc.is_latched());
+        assert_eq!(rtc.register_read(RTC_REGISTER_SECS as u8), 1);
 
-        std::thread::sleep(Duration::new(1, 0));
-        assert_time!(rtc, [1, 0, 0, 0, 0]);
+        rtc.tick(M_CYCLES_PER_SECOND, Speed::Normal);
+        assert_time!(rtc, [2, 0, 0, 0, 0]);
+        assert_eq!(rtc.register_read(RTC_REGISTER_SECS as u8), 1);
+
+        // Writing 1 again without writing 0 first doesn't latch.
+        rtc.latch_write(1);
+        assert_eq!(rtc.register_read(RTC_REGISTER_SECS as u8), 1);
 
         rtc.latch_write(0);
         rtc.latch_write(1);
-        assert!(!rtc.is_latched());
-        assert_time!(rtc, [2, 0, 0, 0, 0]);
+        assert_eq!(rtc.register_read(RTC_REGISTER_SECS as u8), 2);
+    }
+
+    #[test]
+    fn register_write_keeps_every_bit() {
+        let mut rtc = Rtc::new();
+
+        rtc.register_write(RTC_REGISTER_SECS as u8, 0xFC);
+        rtc.register_write(RTC_REGISTER_MINS as u8, 0x04);
+        rtc.register_write(RTC_REGISTER_HOURS as u8, 0xE8);
+
+        assert_time!(rtc, [0x3C, 0x04, 0x08, 0, 0]);
+        assert_eq!(rtc.register_read(RTC_REGISTER_SECS as u8), 0x3C);
+        assert_eq!(rtc.register_read(RTC_REGISTER_HOURS as u8), 0x08);
+    }
+
+    #[test]
+    fn write_only_updates_written_latched_register() {
+        let mut rtc = Rtc::new();
+
+        rtc.tick(5 * M_CYCLES_PER_SECOND, Speed::Normal);
+        rtc.latch_write(0);
+        rtc.latch_write(1);
+
+        rtc.tick(5 * M_CYCLES_PER_SECOND, Speed::Normal);
+        rtc.register_write(RTC_REGISTER_DAYS_LOW as u8, 3);
+
+        assert_eq!(rtc.get_latched_parts(), [5, 0, 0, 3, 0]);
+        assert_time!(rtc, [10, 0, 0, 3, 0]);
     }
 
     #[test]
@@ -343,7 +584,106 @@ mod tests {
         rtc.register_write(4, RTC_FLAG_HALT);
         assert!(rtc.is_halted());
 
-        std::thread::sleep(Duration::new(2, 0));
+        rtc.tick(2 * M_CYCLES_PER_SECOND, Speed::Normal);
         assert_time!(rtc, [0, 0, 0, 0, 0]);
+
+        // Time that passed while halted isn't counted once the RTC resumes.
+        rtc.register_write(4, 0);
+        rtc.tick(M_CYCLES_PER_SECOND, Speed::Normal);
+        assert_time!(rtc, [1, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn set_and_advance() {
+        let mut rtc = Rtc::new();
+        rtc.set_time(RtcTime {
+            days: 300,
+            hours: 23,
+            minutes: 90,
+            seconds: 5,
+        });
+
+        let expected = RtcTime {
+            days: 301,
+            hours: 0,
+            minutes: 30,
+            seconds: 5,
+        };
+        assert_eq!(rtc.time(), expected);
+        assert!(!rtc.has_day_carry());
+
+        // The game doesn't see the new time until it latches it.
+        assert_eq!(rtc.register_read(RTC_REGISTER_HOURS as u8), 0);
+
+        rtc.advance(Duration::from_millis(500));
+        assert_eq!(rtc.time(), expected);
+        rtc.advance(Duration::from_millis(500));
+        assert_eq!(rtc.time().seconds, 6);
+
+        rtc.advance(Duration::from_secs(211 * 86_400));
+        assert_eq!(rtc.time().days, 0);
+        assert!(rtc.has_day_carry());
+
+        rtc.set_day_carry(false);
+        assert!(!rtc.has_day_carry());
+
+        rtc.register_write(RTC_REGISTER_DAYS_HIGH as u8, RTC_FLAG_HALT);
+        rtc.advance(Duration::from_secs(60));
+        assert_eq!(rtc.time().minutes, 30);
+    }
+
+    #[test]
+    fn set_time_while_halted() {
+        let mut rtc = Rtc::new();
+        rtc.register_write(RTC_REGISTER_DAYS_HIGH as u8, RTC_FLAG_HALT);
+
+        rtc.set_time(RtcTime {
+            days: 3,
+            hours: 4,
+            minutes: 5,
+            seconds: 6,
+        });
+
+        assert_eq!(rtc.get_time_parts(), [6, 5, 4, 3, RTC_FLAG_HALT]);
+        assert!(rtc.is_halted());
+        assert!(!rtc.has_day_carry());
+    }
+
+    #[test]
+    fn footer() {
+        let mut rtc = Rtc::load(unix_now(), [5, 4, 3, 2, 1]);
+        rtc.latch_write(0);
+        rtc.latch_write(1);
+        rtc.register_write(RTC_REGISTER_HOURS as u8, 7);
+
+        let mut footer = rtc.export();
+        assert_eq!(footer[..8], [5, 0, 0, 0, 4, 0, 0, 0]);
+        assert_eq!(footer[20..24], [5, 0, 0, 0]);
+
+        // An hour passes between saving and loading.
+        let timestamp = u64::from_le_bytes(footer[40..].try_into().unwrap()) - 3600;
+        footer[40..].copy_from_slice(&timestamp.to_le_bytes());
+
+        let mut rtc = Rtc::new();
+        assert!(rtc.import(&footer));
+        assert_time!(rtc, [5, 4, 8, 2, 1]);
+        assert_eq!(rtc.get_latched_parts(), [5, 4, 7, 2, 1]);
+
+        let mut rtc = Rtc::new();
+        assert!(rtc.import(&footer[..LEGACY_FOOTER_SIZE]));
+        assert_time!(rtc, [5, 4, 8, 2, 1]);
+
+        assert!(!rtc.import(&footer[..40]));
+    }
+
+    #[test]
+    fn halted_footer_does_not_advance() {
+        let mut footer = Rtc::load(0, [0, 0, 0, 0, RTC_FLAG_HALT]).export();
+        footer[40..].copy_from_slice(&0u64.to_le_bytes());
+
+        let mut rtc = Rtc::new();
+        assert!(rtc.import(&footer));
+        assert!(rtc.is_halted());
+        assert_eq!(rtc.get_time_parts(), [0, 0, 0, 0, RTC_FLAG_HALT]);
     }
 }
@@ -1,41 +1,60 @@
-use super::ControllerType;
-use crate::cartridge::constants::RAM_SIZE;
+use super::{has_battery, import_ram, map_ram_address, ControllerType, ImportError};
+use crate::cartridge::constants::{CONTROLLER_TYPE, RAM_SIZE};
 use crate::cartridge::{get_ram_size, MemoryBankController};
-use crate::constants::{EXTERNAL_RAM_SIZE, EXTERNAL_RAM_START, ROM_BANK_SIZE};
+use crate::constants::{ROM0_END, ROM0_START, ROM_BANK_END, ROM_BANK_SIZE, ROM_BANK_START};
 
+/// MBC5, which supports up to 8 MiB of ROM and 128 KiB of RAM.
+///
+/// Unlike earlier MBCs, the 9-bit ROM bank number is used as-is, so bank 0 can be mapped to
+/// `$4000-$7FFF` too.
+///
+/// Rumble carts use bit 3 of the RAM bank register to turn the motor on and off, which leaves
+/// them with 8 RAM banks at most.
 pub struct Mbc5 {
     rom: Vec<u8>,
     ram: Vec<u8>,
+    battery: bool,
+    rumble: bool,
+
+    /// The ROM bank bits that exist on the cart. Banks past the end of the ROM wrap around.
+    rom_bank_mask: usize,
     rom_bank: u16,
     ram_bank: u8,
     ram_enabled: bool,
+
+    /// Whether the rumble motor is on, and its state as of the last call to `poll_rumble`.
+    motor: bool,
+    reported_motor: bool,
 }
 
 impl Mbc5 {
     pub fn new(rom: Vec<u8>) -> Self {
         let ram_size = get_ram_size(rom[RAM_SIZE]).expect("Unsupported RAM size");
         let ram = vec![0; ram_size];
-
-        // TODO MBC5s also included rumble pack support, but I'm not sure if that's really relevant
-        // for the purposes of emulation. Since the write is to a RAM bank register, I might need
-        // to at least account for writes to that register so it doesn't affect the current RAM
-        // bank.
+        let battery = has_battery(&rom);
+        let rumble = matches!(rom[CONTROLLER_TYPE], 0x1C..=0x1E);
+        let rom_bank_mask = (rom.len() / ROM_BANK_SIZE).max(1).next_power_of_two() - 1;
 
         Self {
             rom,
             ram,
+            battery,
+            rumble,
+            rom_bank_mask,
             rom_bank: 1,
             ram_bank: 0,
             ram_enabled: false,
+            motor: false,
+            reported_motor: false,
         }
     }
 
-    fn map_rom_address(&self, address: usize) -> usize {
-        self.rom_bank as usize * ROM_BANK_SIZE + address
-    }
+    fn ram_address(&self, address: usize) -> Option<usize> {
+        if !self.ram_enabled || self.ram.is_empty() {
+            return None;
+        }
 
-    fn map_ram_address(&self, address: usize) -> usize {
-        self.ram_bank as usize * EXTERNAL_RAM_SIZE + (address - EXTERNAL_RAM_START)
+        Some(map_ram_address(self.ram_bank, address) % self.ram.len())
     }
 }
 
@@ -45,37 +64,146 @@ impl MemoryBankController for Mbc5 {
     }
 
     fn rom_read(&self, address: usize) -> u8 {
-        *self.rom.get(self.map_rom_address(address)).unwrap_or(&0xFF)
+        let bank = match address {
+            ROM0_START..=ROM0_END => 0,
+            ROM_BANK_START..=ROM_BANK_END => self.rom_bank as usize & self.rom_bank_mask,
+            _ => panic!("ROM read out of range for MBC5: {:#X}", address),
+        };
+
+        *self
+            .rom
+            .get(bank * ROM_BANK_SIZE + (address % ROM_BANK_SIZE))
+            .unwrap_or(&0xFF)
     }
 
     fn rom_write(&mut self, address: usize, value: u8) {
         match address {
-            0x0000..=0x1FFF => self.ram_enabled = value & 0x0A != 0,
+            0x0000..=0x1FFF => self.ram_enabled = value & 0x0F == 0x0A,
             0x2000..=0x2FFF => self.rom_bank = (self.rom_bank & 0x100) | value as u16,
-            0x3000..=0x3FFF => self.rom_bank = (self.rom_bank & 0xFF) | (value & 1) as u16,
+            0x3000..=0x3FFF => self.rom_bank = (self.rom_bank & 0xFF) | ((value as u16 & 1) << 8),
+            0x4000..=0x5FFF if self.rumble => {
+                self.ram_bank = value & 0x07;
+                self.motor = value & 0x08 != 0;
+            }
             0x4000..=0x5FFF => self.ram_bank = value & 0x0F,
             _ => (),
         }
     }
 
     fn ram_read(&self, address: usize) -> u8 {
-        if !self.ram_enabled {
-            return 0xFF;
+        match self.ram_address(address) {
+            Some(address) => self.ram[address],
+            None => 0xFF,
         }
-
-        *self.ram.get(self.map_ram_address(address)).unwrap_or(&0xFF)
     }
 
     fn ram_write(&mut self, address: usize, value: u8) {
-        if !self.ram_enabled {
-            return;
+        if let Some(address) = self.ram_address(address) {
+            self.ram[address] = value;
         }
+    }
 
-        let address = self.map_ram_address(address);
-        let slot = self.ram.get_mut(address);
+    fn has_battery(&self) -> bool {
+        self.battery
+    }
+
+    fn export_ram(&self) -> Vec<u8> {
+        self.ram.clone()
+    }
 
-        if let Some(slot) = slot {
-            *slot = value;
+    fn import_ram(&mut self, data: &[u8]) -> Result<(), ImportError> {
+        import_ram(&mut self.ram, data)
+    }
+
+    fn has_rumble(&self) -> bool {
+        self.rumble
+    }
+
+    fn poll_rumble(&mut self) -> Option<bool> {
+        if self.motor == self.reported_motor {
+            return None;
         }
+
+        self.reported_motor = self.motor;
+
+        Some(self.motor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a 512 bank ROM with 128 KiB of RAM, with each bank starting with the low byte of its
+    /// bank number and followed by the high byte.
+    fn rom(controller_type: u8) -> Vec<u8> {
+        let mut rom = vec![0; 512 * ROM_BANK_SIZE];
+
+        for bank in 0..512 {
+            rom[bank * ROM_BANK_SIZE] = bank as u8;
+            rom[bank * ROM_BANK_SIZE + 1] = (bank >> 8) as u8;
+        }
+
+        rom[CONTROLLER_TYPE] = controller_type;
+        rom[RAM_SIZE] = 0x04; // 128 KiB
+
+        rom
+    }
+
+    #[test]
+    fn rom_banks() {
+        let mut controller = Mbc5::new(rom(0x1B)); // MBC5+RAM+BATTERY
+        assert_eq!(controller.rom_read(0x0000), 0x00);
+        assert_eq!(controller.rom_read(0x4000), 0x01);
+
+        // Bank 0 can be mapped to $4000-$7FFF.
+        controller.rom_write(0x2000, 0x00);
+        assert_eq!(controller.rom_read(0x4000), 0x00);
+        assert_eq!(controller.rom_read(0x4001), 0x00);
+
+        controller.rom_write(0x2000, 0x23);
+        controller.rom_write(0x3000, 0x01);
+        assert_eq!(controller.rom_read(0x4000), 0x23);
+        assert_eq!(controller.rom_read(0x4001), 0x01);
+        assert_eq!(controller.rom_read(0x0000), 0x00);
+        assert_eq!(controller.rom_read(0x0001), 0x00);
+    }
+
+    #[test]
+    fn ram_banks() {
+        let mut controller = Mbc5::new(rom(0x1B));
+        controller.rom_write(0x0000, 0x0A);
+
+        for bank in 0..16 {
+            controller.rom_write(0x4000, bank);
+            controller.ram_write(0xA000, bank + 1);
+        }
+
+        for bank in 0..16 {
+            controller.rom_write(0x4000, bank);
+            assert_eq!(controller.ram_read(0xA000), bank + 1);
+        }
+
+        assert!(!controller.has_rumble());
+        assert_eq!(controller.poll_rumble(), None);
+    }
+
+    #[test]
+    fn rumble() {
+        let mut controller = Mbc5::new(rom(0x1E)); // MBC5+RUMBLE+RAM+BATTERY
+        assert!(controller.has_rumble());
+        controller.rom_write(0x0000, 0x0A);
+
+        // The motor bit doesn't select a RAM bank.
+        controller.rom_write(0x4000, 0x02);
+        controller.ram_write(0xA000, 0x42);
+        controller.rom_write(0x4000, 0x0A);
+        assert_eq!(controller.ram_read(0xA000), 0x42);
+
+        assert_eq!(controller.poll_rumble(), Some(true));
+        assert_eq!(controller.poll_rumble(), None);
+
+        controller.rom_write(0x4000, 0x02);
+        assert_eq!(controller.poll_rumble(), Some(false));
     }
 }
@@ -2,9 +2,12 @@ use std::fmt::Display;
 
 use crate::cartridge::constants::CONTROLLER_TYPE;
 use crate::constants::{EXTERNAL_RAM_SIZE, EXTERNAL_RAM_START, ROM_BANK_SIZE};
+use gb_rs_common::clock::Speed;
+use mbc3::rtc::Rtc;
 
 pub mod mbc0;
 pub mod mbc1;
+pub mod mbc2;
 pub mod mbc3;
 pub mod mbc5;
 
@@ -37,6 +40,47 @@ pub trait MemoryBankController {
     /// RAM may be ignored, or may be interpreted in MBC-specific ways.
     fn ram_write(&mut self, address: usize, value: u8);
 
+    /// Advances any hardware on the cart that runs off the CPU clock by `m_cycles` M-cycles at
+    /// the given speed.
+    fn tick(&mut self, _m_cycles: u8, _speed: Speed) {}
+
+    /// Returns the cart's real time clock, if it has one.
+    fn rtc(&self) -> Option<&Rtc> {
+        None
+    }
+
+    fn rtc_mut(&mut self) -> Option<&mut Rtc> {
+        None
+    }
+
+    /// Returns `true` if the cart has a battery, which keeps the contents of its RAM around while
+    /// the Game Boy is off.
+    fn has_battery(&self) -> bool;
+
+    /// Returns the contents of the cart's RAM, in the format of a `.sav` file.
+    fn export_ram(&self) -> Vec<u8>;
+
+    /// Returns a snapshot of everything [`export_ram`](Self::export_ram) saves, apart from what
+    /// changes on its own, like the time kept by an RTC. Comparing snapshots tells whether the
+    /// `.sav` file needs writing again.
+    fn ram_snapshot(&self) -> Vec<u8> {
+        self.export_ram()
+    }
+
+    /// Replaces the contents of the cart's RAM with the contents of a `.sav` file.
+    fn import_ram(&mut self, data: &[u8]) -> Result<(), ImportError>;
+
+    /// Returns `true` if the cart has a rumble motor.
+    fn has_rumble(&self) -> bool {
+        false
+    }
+
+    /// Returns the state of the rumble motor (`true` if it's on) if it has been switched on or off
+    /// since the last call, or `None` if it hasn't.
+    fn poll_rumble(&mut self) -> Option<bool> {
+        None
+    }
+
     fn get_controller_type(&self) -> ControllerType;
 }
 
@@ -44,6 +88,7 @@ pub trait MemoryBankController {
 pub enum ControllerType {
     Mbc0,
     Mbc1,
+    Mbc2,
     Mbc3,
     Mbc5,
 }
@@ -54,6 +99,7 @@ impl ControllerType {
         match self {
             ControllerType::Mbc0 => Box::new(mbc0::Mbc0::new(rom)),
             ControllerType::Mbc1 => Box::new(mbc1::Mbc1::new(rom)),
+            ControllerType::Mbc2 => Box::new(mbc2::Mbc2::new(rom)),
             ControllerType::Mbc3 => Box::new(mbc3::Mbc3::new(rom)),
             ControllerType::Mbc5 => Box::new(mbc5::Mbc5::new(rom)),
         }
@@ -64,6 +110,7 @@ impl ControllerType {
         match rom[CONTROLLER_TYPE] {
             0x00 => Ok(ControllerType::Mbc0.create(rom)),
             0x01..=0x03 => Ok(ControllerType::Mbc1.create(rom)),
+            0x05..=0x06 => Ok(ControllerType::Mbc2.create(rom)),
             0x0F..=0x13 => Ok(ControllerType::Mbc3.create(rom)),
             0x19..=0x1E => Ok(ControllerType::Mbc5.create(rom)),
             x => Err(CreateError::UnsupportedControllerType(x)),
@@ -76,6 +123,7 @@ impl Display for ControllerType {
         f.write_str(match self {
             Self::Mbc0 => "mbc0",
             Self::Mbc1 => "mbc1",
+            Self::Mbc2 => "mbc2",
             Self::Mbc3 => "mbc3",
             Self::Mbc5 => "mbc5",
         })
@@ -84,6 +132,12 @@ impl Display for ControllerType {
 
 pub type CreateResult = Result<Box<dyn MemoryBankController>, CreateError>;
 
+#[derive(Debug, thiserror::Error)]
+pub enum ImportError {
+    #[error("save data should be {expected} bytes, but is {actual} bytes")]
+    Size { expected: usize, actual: usize },
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum CreateError {
     /// Indicates the value in the ROM's [`CONTROLLER_TYPE`] header could not be mapped to a
@@ -95,6 +149,28 @@ pub enum CreateError {
     UnsupportedRamSize(u8),
 }
 
+/// Returns `true` if the ROM's [`CONTROLLER_TYPE`] header says the cart has a battery.
+pub fn has_battery(rom: &[u8]) -> bool {
+    matches!(
+        rom[CONTROLLER_TYPE],
+        0x03 | 0x06 | 0x09 | 0x0D | 0x0F | 0x10 | 0x13 | 0x1B | 0x1E | 0x22 | 0xFF
+    )
+}
+
+/// Copies the contents of a `.sav` file into `ram`, which must be exactly the same size.
+pub fn import_ram(ram: &mut [u8], data: &[u8]) -> Result<(), ImportError> {
+    if ram.len() != data.len() {
+        return Err(ImportError::Size {
+            expected: ram.len(),
+            actual: data.len(),
+        });
+    }
+
+    ram.copy_from_slice(data);
+
+    Ok(())
+}
+
 /// Maps a ROM memory address to an absolute banked address.
 ///
 /// This function assumes banks are stored in a single `Vec`, _including_ ROM0.
@@ -1,6 +1,9 @@
+use crate::cartridge::mbc::mbc3::rtc::Rtc;
 use crate::cartridge::mbc::MemoryBankController;
 use crate::constants::EXTERNAL_RAM_SIZE;
 use gb_rs_common::bytes::bytes_to_word;
+use gb_rs_common::clock::Speed;
+use gb_rs_common::model::BootHeader;
 use gb_rs_common::DeviceMode;
 
 pub mod constants;
@@ -30,6 +33,8 @@ pub struct Cartridge {
     pub licensee_id: u16,
     pub sgb_support: bool,
     pub version: u8,
+    pub header_checksum: u8,
+    pub title_checksum: u8,
     pub controller: Box<dyn MemoryBankController>,
 }
 
@@ -41,6 +46,8 @@ impl Cartridge {
             licensee_id: get_licensee_id(&rom),
             sgb_support: get_sgb_support(&rom),
             version: get_version(&rom),
+            header_checksum: get_header_checksum(&rom),
+            title_checksum: get_title_checksum(&rom),
             controller: mbc::ControllerType::create_for_rom(rom)?,
         })
     }
@@ -65,6 +72,22 @@ impl Cartridge {
         self.version
     }
 
+    /// Returns `true` if the cartridge was published by Nintendo.
+    pub fn is_nintendo_licensee(&self) -> bool {
+        // New licensee codes are stored as two ASCII characters, so Nintendo's "01" is `$3031`.
+        matches!(self.licensee_id, 0x01 | 0x3031)
+    }
+
+    /// Returns the parts of the header that the boot ROM inspects.
+    pub fn boot_header(&self) -> BootHeader {
+        BootHeader {
+            header_checksum: self.header_checksum,
+            title_checksum: self.title_checksum,
+            nintendo_licensee: self.is_nintendo_licensee(),
+            color: !matches!(self.device_mode, SupportedDeviceMode::Classic),
+        }
+    }
+
     pub fn rom_read(&self, address: usize) -> u8 {
         self.controller.rom_read(address)
     }
@@ -80,6 +103,42 @@ impl Cartridge {
     pub fn ram_write(&mut self, address: usize, value: u8) {
         self.controller.ram_write(address, value);
     }
+
+    pub fn tick(&mut self, m_cycles: u8, speed: Speed) {
+        self.controller.tick(m_cycles, speed);
+    }
+
+    pub fn rtc(&self) -> Option<&Rtc> {
+        self.controller.rtc()
+    }
+
+    pub fn rtc_mut(&mut self) -> Option<&mut Rtc> {
+        self.controller.rtc_mut()
+    }
+
+    pub fn has_battery(&self) -> bool {
+        self.controller.has_battery()
+    }
+
+    pub fn export_ram(&self) -> Vec<u8> {
+        self.controller.export_ram()
+    }
+
+    pub fn ram_snapshot(&self) -> Vec<u8> {
+        self.controller.ram_snapshot()
+    }
+
+    pub fn import_ram(&mut self, data: &[u8]) -> Result<(), mbc::ImportError> {
+        self.controller.import_ram(data)
+    }
+
+    pub fn has_rumble(&self) -> bool {
+        self.controller.has_rumble()
+    }
+
+    pub fn poll_rumble(&mut self) -> Option<bool> {
+        self.controller.poll_rumble()
+    }
 }
 
 pub type CartridgeResult = Result<Cartridge, CartridgeError>;
@@ -176,6 +235,20 @@ pub fn get_version(rom: &[u8]) -> u8 {
     rom[constants::VERSION]
 }
 
+/// Retrieves the header checksum, which is computed over the bytes at `$0134-$014C`.
+pub fn get_header_checksum(rom: &[u8]) -> u8 {
+    rom[constants::HEADER_CHECKSUM]
+}
+
+/// Computes the sum of all 16 possible title bytes, regardless of the actual title length.
+///
+/// Color hardware uses this to pick a palette when running a Classic cartridge.
+pub fn get_title_checksum(rom: &[u8]) -> u8 {
+    rom[constants::TITLE_START..=constants::GBC_SUPPORT_TYPE]
+        .iter()
+        .fold(0u8, |sum, &byte| sum.wrapping_add(byte))
+}
+
 /// Retrieves the size of the cartridge RAM.
 ///
 /// A map of RAM sizes can be found
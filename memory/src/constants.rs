@@ -23,12 +23,54 @@ pub const RAM_BANK_SIZE: usize = RAM_BANK_END - RAM_BANK_START + 1;
 
 pub const ECHO_START: usize = 0xE000;
 pub const ECHO_END: usize = 0xFDFF;
+pub const ECHO_OFFSET: usize = ECHO_START - RAM0_START;
 
 pub const OAM_START: usize = 0xFE00;
 pub const OAM_END: usize = 0xFE9F;
 pub const OAM_SIZE: usize = OAM_END - OAM_START + 1;
 
+pub const UNUSABLE_START: usize = 0xFEA0;
+pub const UNUSABLE_END: usize = 0xFEFF;
+
+pub const JOYPAD: usize = 0xFF00;
+pub const SERIAL_DATA: usize = 0xFF01;
+pub const SERIAL_CONTROL: usize = 0xFF02;
+pub const DIVIDER: usize = 0xFF04;
+pub const TIMER_COUNTER: usize = 0xFF05;
+pub const TIMER_MODULO: usize = 0xFF06;
+pub const TIMER_CONTROL: usize = 0xFF07;
 pub const INTERRUPT_FLAGS: usize = 0xFF0F;
+pub const AUDIO_START: usize = 0xFF10;
+pub const AUDIO_END: usize = 0xFF26;
+pub const WAVE_RAM_START: usize = 0xFF30;
+pub const WAVE_RAM_END: usize = 0xFF3F;
+pub const LCD_CONTROL: usize = 0xFF40;
+pub const LCD_STATUS: usize = 0xFF41;
+pub const SCROLL_Y: usize = 0xFF42;
+pub const SCROLL_X: usize = 0xFF43;
+pub const LCD_Y: usize = 0xFF44;
+pub const LCD_Y_COMPARE: usize = 0xFF45;
+pub const OAM_DMA: usize = 0xFF46;
+pub const BG_PALETTE: usize = 0xFF47;
+pub const OBJ_PALETTE_0: usize = 0xFF48;
+pub const OBJ_PALETTE_1: usize = 0xFF49;
+pub const WINDOW_Y: usize = 0xFF4A;
+pub const WINDOW_X: usize = 0xFF4B;
+pub const SPEED_SWITCH: usize = 0xFF4D;
+pub const VRAM_BANK: usize = 0xFF4F;
+pub const BOOT_ROM_DISABLE: usize = 0xFF50;
+pub const VRAM_DMA_SOURCE_HIGH: usize = 0xFF51;
+pub const VRAM_DMA_SOURCE_LOW: usize = 0xFF52;
+pub const VRAM_DMA_DESTINATION_HIGH: usize = 0xFF53;
+pub const VRAM_DMA_DESTINATION_LOW: usize = 0xFF54;
+pub const VRAM_DMA_CONTROL: usize = 0xFF55;
+pub const INFRARED: usize = 0xFF56;
+pub const BG_PALETTE_INDEX: usize = 0xFF68;
+pub const BG_PALETTE_DATA: usize = 0xFF69;
+pub const OBJ_PALETTE_INDEX: usize = 0xFF6A;
+pub const OBJ_PALETTE_DATA: usize = 0xFF6B;
+pub const OBJ_PRIORITY: usize = 0xFF6C;
+pub const WRAM_BANK: usize = 0xFF70;
 
 pub const IO_START: usize = 0xFF00;
 pub const IO_END: usize = 0xFF7F;
@@ -39,3 +81,6 @@ pub const HRAM_END: usize = 0xFFFE;
 pub const HRAM_SIZE: usize = HRAM_END - HRAM_START + 1;
 
 pub const INTERRUPT_ENABLE: usize = 0xFFFF;
+
+pub const DMG_BOOT_ROM_SIZE: usize = 0x100;
+pub const CGB_BOOT_ROM_SIZE: usize = 0x900;
@@ -0,0 +1,51 @@
+use bitflags::bitflags;
+
+bitflags! {
+    /// The LCD control register (`LCDC`, [`LCD_CONTROL`](crate::constants::LCD_CONTROL)).
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Lcdc: u8 {
+        /// Enables the background and window in Classic mode. In Color mode, this instead gives
+        /// objects priority over the background and window when cleared.
+        const BG_WINDOW_ENABLE = 1 << 0;
+        const OBJ_ENABLE = 1 << 1;
+
+        /// Selects 8x16 objects instead of 8x8.
+        const OBJ_SIZE = 1 << 2;
+
+        /// Selects the tile map at `$9C00` for the background, instead of `$9800`.
+        const BG_TILE_MAP = 1 << 3;
+
+        /// Selects unsigned tile indices from `$8000` for the background and window, instead of
+        /// signed indices from `$9000`.
+        const TILE_DATA = 1 << 4;
+        const WINDOW_ENABLE = 1 << 5;
+
+        /// Selects the tile map at `$9C00` for the window, instead of `$9800`.
+        const WINDOW_TILE_MAP = 1 << 6;
+        const LCD_ENABLE = 1 << 7;
+    }
+}
+
+bitflags! {
+    /// The LCD status register (`STAT`, [`LCD_STATUS`](crate::constants::LCD_STATUS)).
+    ///
+    /// The mode and [`LYC_EQUAL`](Self::LYC_EQUAL) bits are read-only, and are set by the PPU.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Stat: u8 {
+        /// The PPU's current mode, from 0 (H-Blank) to 3 (drawing).
+        const MODE = 0b11;
+
+        /// Set while `LY` equals `LYC`.
+        const LYC_EQUAL = 1 << 2;
+        const HBLANK_INTERRUPT = 1 << 3;
+        const VBLANK_INTERRUPT = 1 << 4;
+        const OAM_INTERRUPT = 1 << 5;
+        const LYC_INTERRUPT = 1 << 6;
+    }
+}
+
+impl Stat {
+    pub fn mode(self) -> u8 {
+        (self & Self::MODE).bits()
+    }
+}
@@ -0,0 +1,171 @@
+use crate::constants::*;
+use gb_rs_common::DeviceMode;
+
+pub use lcd::{Lcdc, Stat};
+
+mod lcd;
+
+/// The bits of `NR10-NR52` that always read as 1, since they're either unused or write-only.
+/// `$FF15` and `$FF1F` aren't connected to anything, so they read as `$FF`.
+const AUDIO_READ_MASKS: [u8; AUDIO_END - AUDIO_START + 1] = [
+    0x80, 0x3F, 0x00, 0xFF, 0xBF, 0xFF, 0x3F, 0x00, 0xFF, 0xBF, 0x7F, 0xFF, 0x9F, 0xFF, 0xBF, 0xFF,
+    0xFF, 0x00, 0x00, 0xBF, 0x00, 0x00, 0x70,
+];
+
+/// The IO registers at `$FF00-$FF7F` that aren't owned by a more specific component (e.g. the
+/// [`Joypad`](crate::joypad::Joypad)), which [`Memory`](crate::Memory) handles itself.
+///
+/// Every register has a read mask of bits that always read as 1, covering unused bits and
+/// write-only registers, as well as a set of read-only bits that writes from the CPU leave alone.
+/// Addresses that don't belong to any register in the current mode read as `$FF`.
+#[derive(Debug)]
+pub struct Io {
+    registers: [u8; IO_SIZE],
+    mode: DeviceMode,
+}
+
+impl Io {
+    pub fn new(mode: DeviceMode) -> Self {
+        Self {
+            registers: [0; IO_SIZE],
+            mode,
+        }
+    }
+
+    /// Reads a register as the CPU sees it.
+    pub fn read(&self, address: usize) -> u8 {
+        self.get(address) | self.read_mask(address)
+    }
+
+    /// Writes to a register from the CPU, leaving its read-only bits untouched.
+    pub fn write(&mut self, address: usize, value: u8) {
+        let read_only = Self::read_only_mask(address);
+        let register = &mut self.registers[address - IO_START];
+
+        *register = (*register & read_only) | (value & !read_only);
+    }
+
+    /// Returns the raw value of a register, including any bits that the CPU can't read.
+    pub fn get(&self, address: usize) -> u8 {
+        self.registers[address - IO_START]
+    }
+
+    /// Sets the raw value of a register, including its read-only bits. This is meant for the
+    /// component that owns the register, rather than the CPU.
+    pub fn set(&mut self, address: usize, value: u8) {
+        self.registers[address - IO_START] = value;
+    }
+
+    pub fn lcdc(&self) -> Lcdc {
+        Lcdc::from_bits_retain(self.get(LCD_CONTROL))
+    }
+
+    pub fn stat(&self) -> Stat {
+        Stat::from_bits_truncate(self.get(LCD_STATUS))
+    }
+
+    pub fn scy(&self) -> u8 {
+        self.get(SCROLL_Y)
+    }
+
+    pub fn scx(&self) -> u8 {
+        self.get(SCROLL_X)
+    }
+
+    pub fn ly(&self) -> u8 {
+        self.get(LCD_Y)
+    }
+
+    pub fn lyc(&self) -> u8 {
+        self.get(LCD_Y_COMPARE)
+    }
+
+    pub fn bgp(&self) -> u8 {
+        self.get(BG_PALETTE)
+    }
+
+    pub fn obp0(&self) -> u8 {
+        self.get(OBJ_PALETTE_0)
+    }
+
+    pub fn obp1(&self) -> u8 {
+        self.get(OBJ_PALETTE_1)
+    }
+
+    pub fn wy(&self) -> u8 {
+        self.get(WINDOW_Y)
+    }
+
+    pub fn wx(&self) -> u8 {
+        self.get(WINDOW_X)
+    }
+
+    /// Returns the bits of the register at `address` that always read as 1.
+    fn read_mask(&self, address: usize) -> u8 {
+        let color = matches!(self.mode, DeviceMode::Color);
+
+        match address {
+            AUDIO_START..=AUDIO_END => AUDIO_READ_MASKS[address - AUDIO_START],
+            WAVE_RAM_START..=WAVE_RAM_END => 0x00,
+            LCD_STATUS => 0x80,
+            LCD_CONTROL | SCROLL_Y..=LCD_Y_COMPARE | BG_PALETTE..=WINDOW_X => 0x00,
+            INFRARED if color => 0x3C,
+            BG_PALETTE_INDEX | OBJ_PALETTE_INDEX if color => 0x40,
+            BG_PALETTE_DATA | OBJ_PALETTE_DATA if color => 0x00,
+            OBJ_PRIORITY if color => 0xFE,
+            _ => 0xFF,
+        }
+    }
+
+    /// Returns the bits of the register at `address` that can't be written to by the CPU.
+    fn read_only_mask(address: usize) -> u8 {
+        match address {
+            // The PPU mode and LY=LYC flag
+            LCD_STATUS => 0x07,
+            LCD_Y => 0xFF,
+            // Whether each audio channel is on
+            AUDIO_END => 0x0F,
+            // Whether an infrared signal is being received
+            INFRARED => 0x02,
+            _ => 0x00,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_reads_and_writes() {
+        let mut io = Io::new(DeviceMode::Classic);
+
+        // Unused bits read as 1.
+        io.write(LCD_STATUS, 0x00);
+        assert_eq!(io.read(LCD_STATUS), 0x80);
+
+        // Write-only registers read as $FF, but keep the value written.
+        io.write(0xFF13, 0x12);
+        assert_eq!(io.read(0xFF13), 0xFF);
+        assert_eq!(io.get(0xFF13), 0x12);
+
+        // Read-only bits are left alone.
+        io.set(LCD_STATUS, 0x02);
+        io.write(LCD_STATUS, 0x7D);
+        assert_eq!(io.read(LCD_STATUS), 0xFA);
+        assert_eq!(io.stat().mode(), 2);
+
+        io.write(LCD_Y, 0x12);
+        assert_eq!(io.ly(), 0x00);
+
+        // Color registers don't exist in Classic mode.
+        io.write(OBJ_PRIORITY, 0x00);
+        assert_eq!(io.read(OBJ_PRIORITY), 0xFF);
+
+        io.write(LCD_CONTROL, 0x91);
+        assert_eq!(
+            io.lcdc(),
+            Lcdc::LCD_ENABLE | Lcdc::TILE_DATA | Lcdc::BG_WINDOW_ENABLE
+        );
+    }
+}
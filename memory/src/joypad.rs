@@ -0,0 +1,107 @@
+use bitflags::bitflags;
+
+bitflags! {
+    /// A set of buttons on the joypad.
+    ///
+    /// The directions occupy the lower nibble and the action buttons the upper nibble, in the
+    /// same order as the lines they're read through in `P1`.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct Buttons: u8 {
+        const RIGHT = 1 << 0;
+        const LEFT = 1 << 1;
+        const UP = 1 << 2;
+        const DOWN = 1 << 3;
+        const A = 1 << 4;
+        const B = 1 << 5;
+        const SELECT = 1 << 6;
+        const START = 1 << 7;
+    }
+}
+
+/// The joypad register (`P1`, [`JOYPAD`](crate::constants::JOYPAD)).
+///
+/// The buttons are wired up as a 2x4 matrix. Writing a 0 to bit 4 (`P14`) selects the directions
+/// and writing a 0 to bit 5 (`P15`) selects the action buttons, after which the lower nibble reads
+/// a 0 for each pressed button in the selected groups. The joypad interrupt is requested whenever
+/// one of these lines goes from high to low.
+#[derive(Debug, Default)]
+pub struct Joypad {
+    select: u8,
+    pressed: Buttons,
+}
+
+impl Joypad {
+    pub fn pressed(&self) -> Buttons {
+        self.pressed
+    }
+
+    pub fn read(&self) -> u8 {
+        0xC0 | self.select | self.lines()
+    }
+
+    /// Writes to the select lines. Returns `true` if the joypad interrupt should be requested.
+    pub fn write(&mut self, value: u8) -> bool {
+        let lines = self.lines();
+        self.select = value & 0x30;
+
+        self.has_fallen(lines)
+    }
+
+    /// Sets the buttons that are currently held down. Returns `true` if the joypad interrupt
+    /// should be requested.
+    pub fn set_pressed(&mut self, buttons: Buttons) -> bool {
+        let lines = self.lines();
+        self.pressed = buttons;
+
+        self.has_fallen(lines)
+    }
+
+    /// Returns the state of `P10-P13`, which are pulled high unless a selected button is pressed.
+    fn lines(&self) -> u8 {
+        let mut low = 0;
+
+        if self.select & 0x10 == 0 {
+            low |= self.pressed.bits() & 0x0F;
+        }
+
+        if self.select & 0x20 == 0 {
+            low |= self.pressed.bits() >> 4;
+        }
+
+        !low & 0x0F
+    }
+
+    fn has_fallen(&self, previous: u8) -> bool {
+        previous & !self.lines() != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_selected_buttons() {
+        let mut joypad = Joypad::default();
+
+        // Selecting the action buttons doesn't request an interrupt when nothing is pressed.
+        assert!(!joypad.write(0x10));
+        assert_eq!(joypad.read(), 0xDF);
+
+        assert!(joypad.set_pressed(Buttons::START | Buttons::A | Buttons::UP));
+        assert_eq!(joypad.read(), 0xD6);
+
+        // Pressing a button that isn't selected doesn't request an interrupt.
+        assert!(!joypad.set_pressed(Buttons::START | Buttons::A | Buttons::DOWN));
+
+        assert!(!joypad.write(0x20));
+        assert_eq!(joypad.read(), 0xE7);
+
+        assert!(!joypad.write(0x30));
+        assert_eq!(joypad.read(), 0xFF);
+
+        // Selecting both groups ANDs them together.
+        assert!(joypad.write(0x00));
+        assert_eq!(joypad.read(), 0xC6);
+    }
+}
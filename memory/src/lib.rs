@@ -1,29 +1,91 @@
 use crate::cartridge::{Cartridge, CartridgeError};
 use crate::constants::*;
+use crate::io::Io;
+use crate::joypad::{Buttons, Joypad};
+use crate::oam_dma::OamDma;
+use crate::serial::{LinkCable, Serial};
+use crate::timer::Timer;
+use crate::vram_dma::{VramDma, BLOCK_SIZE};
 use gb_rs_asm::read::Read;
 use gb_rs_common::bytes::{bytes_to_word, word_to_bytes};
+use gb_rs_common::clock::Speed;
+use gb_rs_common::interrupts::Interrupt;
+use gb_rs_common::model::Model;
 use gb_rs_common::DeviceMode;
 
 pub mod cartridge;
 pub mod constants;
+pub mod io;
+pub mod joypad;
+pub mod oam_dma;
+pub mod serial;
+pub mod timer;
+pub mod vram_dma;
 
 pub struct Memory {
     pub cartridge: Cartridge,
     vram: Vec<u8>,
     wram: Vec<u8>,
     oam: Vec<u8>,
-    io: Vec<u8>,
+    io: Io,
     hram: Vec<u8>,
     interrupt_flags: u8,
     interrupt_enable: u8,
+    joypad: Joypad,
+    serial: Serial,
+    timer: Timer,
+    oam_dma: OamDma,
+    vram_dma: VramDma,
+    stall_cycles: u16,
     vram_bank: usize,
     wram_bank: usize,
+    model: Model,
+    device_mode: DeviceMode,
+    double_speed: bool,
+    speed_switch_armed: bool,
+    boot_rom: Option<Vec<u8>>,
 }
 
 impl Memory {
-    pub fn new(rom: Vec<u8>) -> MemoryResult {
+    /// Creates memory in the state the boot ROM leaves it in, ready to run the cartridge from
+    /// `$0100`.
+    pub fn new(rom: Vec<u8>, model: Model) -> MemoryResult {
+        let mut memory = Self::power_on(rom, model)?;
+        memory.init_io();
+
+        Ok(memory)
+    }
+
+    /// Creates memory in its power on state, with `boot_rom` mapped over the cartridge ROM until
+    /// it's disabled by writing to [`BOOT_ROM_DISABLE`].
+    ///
+    /// Boot ROMs are [`DMG_BOOT_ROM_SIZE`] bytes long for Classic models, and
+    /// [`CGB_BOOT_ROM_SIZE`] bytes long for color models. Color boot ROMs are mapped in two parts
+    /// (`$0000-$00FF` and `$0200-$08FF`), leaving the cartridge header visible in between.
+    pub fn with_boot_rom(rom: Vec<u8>, model: Model, boot_rom: Vec<u8>) -> MemoryResult {
+        let expected = if model.is_color() {
+            CGB_BOOT_ROM_SIZE
+        } else {
+            DMG_BOOT_ROM_SIZE
+        };
+
+        if boot_rom.len() != expected {
+            return Err(MemoryError::BootRomSize {
+                model,
+                expected,
+                actual: boot_rom.len(),
+            });
+        }
+
+        let mut memory = Self::power_on(rom, model)?;
+        memory.boot_rom = Some(boot_rom);
+
+        Ok(memory)
+    }
+
+    fn power_on(rom: Vec<u8>, model: Model) -> MemoryResult {
         let cartridge = Cartridge::new(rom)?;
-        let mode = cartridge.get_device_mode().into();
+        let mode = model.device_mode(cartridge.boot_header().color);
 
         let (vram, wram) = match mode {
             DeviceMode::Color => (
@@ -38,21 +100,160 @@ impl Memory {
             vram,
             wram,
             oam: vec![0; OAM_SIZE],
-            io: vec![0; IO_SIZE],
+            io: Io::new(mode),
             hram: vec![0; HRAM_SIZE],
             interrupt_flags: 0,
             interrupt_enable: 0,
+            joypad: Joypad::default(),
+            serial: Serial::new(mode),
+            timer: Timer::default(),
+            oam_dma: OamDma::default(),
+            vram_dma: VramDma::default(),
+            stall_cycles: 0,
             vram_bank: 0,
             wram_bank: 1,
+            model,
+            device_mode: mode,
+            double_speed: false,
+            speed_switch_armed: false,
+            boot_rom: None,
         })
     }
 
+    pub fn get_model(&self) -> Model {
+        self.model
+    }
+
+    pub fn get_device_mode(&self) -> DeviceMode {
+        self.device_mode
+    }
+
+    /// Returns the IO registers that aren't owned by a more specific component.
+    pub fn io(&self) -> &Io {
+        &self.io
+    }
+
+    /// Returns `true` if a boot ROM is mapped over the cartridge ROM.
+    pub fn is_boot_rom_mapped(&self) -> bool {
+        self.boot_rom.is_some()
+    }
+
+    /// Returns the interrupts that are both requested (via `IF`) and enabled (via `IE`).
+    ///
+    /// Only the lower 5 bits of either register are connected to an interrupt source, so the
+    /// upper bits are always masked off.
+    pub fn pending_interrupts(&self) -> u8 {
+        self.interrupt_flags & self.interrupt_enable & 0x1F
+    }
+
+    /// Requests an interrupt by setting its bit in `IF`.
+    ///
+    /// This is the entry point for any component that raises interrupts (e.g. the timer or the
+    /// joypad). The interrupt will be serviced once it's enabled in `IE` and the CPU has `IME`
+    /// set.
+    pub fn request_interrupt(&mut self, interrupt: Interrupt) {
+        self.interrupt_flags |= interrupt.mask();
+    }
+
+    /// Clears an interrupt's bit in `IF`. Called by the CPU when it begins servicing an
+    /// interrupt.
+    pub fn acknowledge_interrupt(&mut self, interrupt: Interrupt) {
+        self.interrupt_flags &= !interrupt.mask();
+    }
+
+    /// Returns the buttons that are currently held down.
+    pub fn buttons(&self) -> Buttons {
+        self.joypad.pressed()
+    }
+
+    /// Sets the buttons that are currently held down, requesting the joypad interrupt if any of
+    /// them were newly pressed and are selected in `P1`.
+    pub fn set_buttons(&mut self, buttons: Buttons) {
+        if self.joypad.set_pressed(buttons) {
+            self.request_interrupt(Interrupt::Joypad);
+        }
+    }
+
+    /// Plugs `cable` into the link port, returning the cable that was plugged in before.
+    pub fn connect_link_cable(&mut self, cable: Box<dyn LinkCable>) -> Box<dyn LinkCable> {
+        self.serial.connect(cable)
+    }
+
+    /// Advances the components driven by the CPU clock by `cycles` M-cycles.
+    pub fn tick(&mut self, cycles: u8) {
+        self.cartridge
+            .tick(cycles, Speed::from_double_speed(self.double_speed));
+
+        for _ in 0..cycles {
+            if self.timer.tick() {
+                self.request_interrupt(Interrupt::Timer);
+            }
+
+            if self.serial.tick(self.timer.counter()) {
+                self.request_interrupt(Interrupt::Serial);
+            }
+
+            if let Some((source, offset)) = self.oam_dma.tick() {
+                self.oam[offset] = self.read_mapped(source as usize);
+            }
+        }
+    }
+
+    /// Signals the start of an H-Blank period, during which an H-Blank VRAM DMA transfer copies
+    /// its next block. This is meant to be called by the PPU as it enters mode 0.
+    ///
+    /// Nothing calls this yet, since there's no PPU, so H-Blank transfers don't advance on their
+    /// own.
+    pub fn enter_hblank(&mut self) {
+        if self.vram_dma.mode() == Some(vram_dma::Mode::HBlank) {
+            self.copy_vram_dma_block();
+        }
+    }
+
+    /// Returns the number of M-cycles the CPU needs to pause for to account for VRAM DMA
+    /// transfers, and resets it to zero.
+    pub fn take_stall_cycles(&mut self) -> u16 {
+        std::mem::take(&mut self.stall_cycles)
+    }
+
+    /// Returns `true` if the CPU is running in CGB double speed mode.
+    pub fn is_double_speed(&self) -> bool {
+        self.double_speed
+    }
+
+    /// Performs a CGB speed switch if one was armed by writing to [`SPEED_SWITCH`] (`KEY1`).
+    ///
+    /// This is triggered by the CPU executing `STOP`, and returns `true` if the speed was
+    /// switched. Speed switches are only possible in [`DeviceMode::Color`].
+    pub fn switch_speed(&mut self) -> bool {
+        if !self.speed_switch_armed {
+            return false;
+        }
+
+        self.speed_switch_armed = false;
+        self.double_speed = !self.double_speed;
+
+        true
+    }
+
     pub fn read_byte(&self, address: u16) -> u8 {
         let address = address as usize;
 
+        // While OAM DMA is running, the CPU can only access HRAM and the IO registers.
+        if self.oam_dma.is_active() && address < IO_START {
+            return 0xFF;
+        }
+
+        self.read_mapped(address)
+    }
+
+    /// Reads from the memory map, regardless of whether the CPU would be able to access it.
+    fn read_mapped(&self, address: usize) -> u8 {
         let slot = match address {
             ROM0_START..=ROM0_END | ROM_BANK_START..=ROM_BANK_END => {
-                return self.cartridge.rom_read(address)
+                return self
+                    .read_boot_rom(address)
+                    .unwrap_or_else(|| self.cartridge.rom_read(address))
             }
             VRAM_START..=VRAM_END => {
                 let address = self.vram_bank * VRAM_SIZE + (address - VRAM_START);
@@ -64,13 +265,10 @@ impl Memory {
                 let address = self.wram_bank * RAM_BANK_SIZE + (address - RAM_BANK_START);
                 self.wram.get(address)
             }
-            ECHO_START..=ECHO_END => {
-                let address = self.wram_bank * RAM_BANK_SIZE + (address - ECHO_START);
-                self.wram.get(address)
-            }
+            ECHO_START..=ECHO_END => return self.read_mapped(address - ECHO_OFFSET),
             OAM_START..=OAM_END => self.oam.get(address - OAM_START),
-            INTERRUPT_FLAGS => Some(&self.interrupt_flags),
-            IO_START..=IO_END => self.io.get(address - IO_START),
+            UNUSABLE_START..=UNUSABLE_END => return self.read_unusable(address),
+            IO_START..=IO_END => return self.read_io(address),
             HRAM_START..=HRAM_END => self.hram.get(address - HRAM_START),
             INTERRUPT_ENABLE => Some(&self.interrupt_enable),
             _ => unreachable!(),
@@ -89,6 +287,10 @@ impl Memory {
     pub fn write_byte(&mut self, address: u16, value: u8) {
         let address = address as usize;
 
+        if self.oam_dma.is_active() && address < IO_START {
+            return;
+        }
+
         let slot = match address {
             ROM0_START..=ROM0_END | ROM_BANK_START..=ROM_BANK_END => {
                 self.cartridge.rom_write(address, value);
@@ -110,12 +312,18 @@ impl Memory {
                 self.wram.get_mut(address)
             }
             ECHO_START..=ECHO_END => {
-                let address = self.wram_bank * RAM_BANK_SIZE + (address - ECHO_START);
-                self.wram.get_mut(address)
+                self.write_byte((address - ECHO_OFFSET) as u16, value);
+
+                return;
             }
             OAM_START..=OAM_END => self.oam.get_mut(address - OAM_START),
-            INTERRUPT_FLAGS => Some(&mut self.interrupt_flags),
-            IO_START..=IO_END => self.io.get_mut(address - IO_START),
+            // Writes to the unusable area are ignored.
+            UNUSABLE_START..=UNUSABLE_END => return,
+            IO_START..=IO_END => {
+                self.write_io(address, value);
+
+                return;
+            }
             HRAM_START..=HRAM_END => self.hram.get_mut(address - HRAM_START),
             INTERRUPT_ENABLE => Some(&mut self.interrupt_enable),
             _ => unreachable!(),
@@ -132,6 +340,181 @@ impl Memory {
         self.write_byte(address, low);
         self.write_byte(address + 1, high);
     }
+
+    /// Reads an IO register, from the component that owns it.
+    fn read_io(&self, address: usize) -> u8 {
+        match address {
+            JOYPAD => self.joypad.read(),
+            SERIAL_DATA | SERIAL_CONTROL => self.serial.read(address),
+            DIVIDER..=TIMER_CONTROL => self.timer.read(address),
+            INTERRUPT_FLAGS => 0xE0 | self.interrupt_flags,
+            OAM_DMA => self.oam_dma.read(),
+            SPEED_SWITCH => self.read_speed_switch(),
+            VRAM_BANK => match self.device_mode {
+                DeviceMode::Color => 0xFE | self.vram_bank as u8,
+                DeviceMode::Classic => 0xFF,
+            },
+            WRAM_BANK => match self.device_mode {
+                DeviceMode::Color => 0xF8 | self.wram_bank as u8,
+                DeviceMode::Classic => 0xFF,
+            },
+            VRAM_DMA_SOURCE_HIGH..=VRAM_DMA_CONTROL => match self.device_mode {
+                DeviceMode::Color => self.vram_dma.read(address),
+                DeviceMode::Classic => 0xFF,
+            },
+            BOOT_ROM_DISABLE => 0xFF,
+            _ => self.io.read(address),
+        }
+    }
+
+    /// Writes to an IO register, passing the write on to the component that owns it.
+    fn write_io(&mut self, address: usize, value: u8) {
+        match address {
+            JOYPAD => {
+                if self.joypad.write(value) {
+                    self.request_interrupt(Interrupt::Joypad);
+                }
+            }
+            SERIAL_DATA | SERIAL_CONTROL => self.serial.write(address, value),
+            DIVIDER..=TIMER_CONTROL => self.timer.write(address, value),
+            INTERRUPT_FLAGS => self.interrupt_flags = value & 0x1F,
+            OAM_DMA => self.oam_dma.write(value),
+            SPEED_SWITCH => self.write_speed_switch(value),
+            VRAM_BANK => {
+                if let DeviceMode::Color = self.device_mode {
+                    self.vram_bank = (value & 0x01) as usize;
+                }
+            }
+            WRAM_BANK => {
+                // Bank 0 is always mapped to $C000-$CFFF, so selecting it selects bank 1 instead.
+                if let DeviceMode::Color = self.device_mode {
+                    self.wram_bank = ((value & 0x07) as usize).max(1);
+                }
+            }
+            VRAM_DMA_SOURCE_HIGH..=VRAM_DMA_CONTROL => {
+                if let DeviceMode::Color = self.device_mode {
+                    self.vram_dma.write(address, value);
+
+                    while self.vram_dma.mode() == Some(vram_dma::Mode::General) {
+                        self.copy_vram_dma_block();
+                    }
+                }
+            }
+            BOOT_ROM_DISABLE => {
+                // Once unmapped, the boot ROM can't be mapped again until the system is reset.
+                if value != 0 {
+                    self.boot_rom = None;
+                }
+            }
+            _ => self.io.write(address, value),
+        }
+    }
+
+    /// Reads from the unusable area between OAM and the IO registers.
+    ///
+    /// Classic models always read `$00`, while color models repeat the upper nibble of the
+    /// address's lower byte (e.g. `$FEB4` reads `$BB`).
+    fn read_unusable(&self, address: usize) -> u8 {
+        if self.model.is_color() {
+            let nibble = (address & 0xF0) as u8;
+            nibble | nibble >> 4
+        } else {
+            0x00
+        }
+    }
+
+    /// Sets the IO registers to the values the boot ROM leaves them with.
+    ///
+    /// See https://gbdev.io/pandocs/Power_Up_Sequence.html#hardware-registers.
+    fn init_io(&mut self) {
+        let color = self.model.is_color();
+
+        let mut audio = [
+            0x80, 0xBF, 0xF3, 0xFF, 0xBF, 0xFF, 0x3F, 0x00, 0xFF, 0xBF, 0x7F, 0xFF, 0x9F, 0xFF,
+            0xBF, 0xFF, 0xFF, 0x00, 0x00, 0xBF, 0x77, 0xF3, 0xF1,
+        ];
+
+        // The SGB doesn't play the boot chime, so the first channel is never turned on.
+        if self.model.is_super() {
+            audio[AUDIO_END - AUDIO_START] = 0xF0;
+        }
+
+        for (address, value) in (AUDIO_START..=AUDIO_END).zip(audio) {
+            self.io.set(address, value);
+        }
+
+        let registers = [
+            (LCD_CONTROL, 0x91),
+            (
+                LCD_STATUS,
+                if self.model == Model::Dmg0 {
+                    0x81
+                } else {
+                    0x85
+                },
+            ),
+            (BG_PALETTE, 0xFC),
+        ];
+
+        for (address, value) in registers {
+            self.io.set(address, value);
+        }
+
+        self.serial
+            .write(SERIAL_CONTROL, if color { 0x7F } else { 0x7E });
+        self.oam_dma = OamDma::with_register(if color { 0x00 } else { 0xFF });
+        self.timer = Timer::with_divider(match self.model {
+            Model::Dmg0 => 0x18,
+            Model::Dmg | Model::Mgb => 0xAB,
+            _ => 0x00,
+        });
+
+        self.interrupt_flags = 0x01;
+    }
+
+    /// Copies the next block of a VRAM DMA transfer, and pauses the CPU for the time it takes.
+    fn copy_vram_dma_block(&mut self) {
+        let (source, destination) = match self.vram_dma.next_block() {
+            Some(block) => block,
+            None => return,
+        };
+
+        for i in 0..BLOCK_SIZE {
+            let value = self.read_mapped(source.wrapping_add(i) as usize);
+            let offset = (destination + i) as usize - VRAM_START;
+
+            self.vram[self.vram_bank * VRAM_SIZE + offset] = value;
+        }
+
+        // A block always takes the same amount of time, which is twice as many cycles in double
+        // speed mode.
+        self.stall_cycles += if self.double_speed { 16 } else { 8 };
+    }
+
+    /// Reads from the boot ROM, if it's mapped and covers `address`.
+    fn read_boot_rom(&self, address: usize) -> Option<u8> {
+        // $0100-$01FF is never covered, since the boot ROM needs to read the cartridge header.
+        if (0x100..0x200).contains(&address) {
+            return None;
+        }
+
+        self.boot_rom.as_ref()?.get(address).copied()
+    }
+
+    fn read_speed_switch(&self) -> u8 {
+        match self.device_mode {
+            DeviceMode::Color => {
+                0x7E | ((self.double_speed as u8) << 7) | self.speed_switch_armed as u8
+            }
+            DeviceMode::Classic => 0xFF,
+        }
+    }
+
+    fn write_speed_switch(&mut self, value: u8) {
+        if let DeviceMode::Color = self.device_mode {
+            self.speed_switch_armed = value & 1 != 0;
+        }
+    }
 }
 
 impl Read for Memory {
@@ -144,6 +527,172 @@ impl Read for Memory {
 pub enum MemoryError {
     #[error("cartridge error: {0}")]
     CartridgeError(#[from] CartridgeError),
+
+    #[error("boot ROM for {model} should be {expected} bytes, but is {actual} bytes")]
+    BootRomSize {
+        model: Model,
+        expected: usize,
+        actual: usize,
+    },
 }
 
 pub type MemoryResult = Result<Memory, MemoryError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a 32 KiB ROM-only cartridge, filled with `fill`.
+    fn rom(fill: u8) -> Vec<u8> {
+        let mut rom = vec![fill; 0x8000];
+        rom[cartridge::constants::CONTROLLER_TYPE] = 0x00;
+        rom[cartridge::constants::RAM_SIZE] = 0x00;
+
+        rom
+    }
+
+    #[test]
+    fn boot_rom_is_unmapped_by_ff50() {
+        let rom = rom(0x11);
+        let boot_rom = vec![0x22; CGB_BOOT_ROM_SIZE];
+
+        assert!(Memory::with_boot_rom(rom.clone(), Model::Dmg, boot_rom.clone()).is_err());
+
+        let mut memory = Memory::with_boot_rom(rom, Model::Cgb, boot_rom).unwrap();
+
+        assert_eq!(memory.read_byte(0x0000), 0x22);
+        assert_eq!(memory.read_byte(0x0100), 0x11);
+        assert_eq!(memory.read_byte(0x0200), 0x22);
+        assert_eq!(memory.read_byte(0x0900), 0x11);
+
+        memory.write_byte(BOOT_ROM_DISABLE as u16, 0x11);
+        memory.write_byte(BOOT_ROM_DISABLE as u16, 0x00);
+
+        assert!(!memory.is_boot_rom_mapped());
+        assert_eq!(memory.read_byte(0x0000), 0x11);
+    }
+
+    #[test]
+    fn oam_dma_copies_and_blocks() {
+        let mut memory = Memory::new(rom(0x00), Model::Dmg).unwrap();
+
+        for i in 0..OAM_SIZE {
+            memory.write_byte((RAM0_START + i) as u16, i as u8);
+        }
+
+        memory.write_byte(OAM_DMA as u16, 0xC0);
+        memory.tick(2);
+
+        // The transfer has started, so only HRAM and the IO registers are accessible.
+        assert_eq!(memory.read_byte(RAM0_START as u16), 0xFF);
+        assert_eq!(memory.read_byte(OAM_DMA as u16), 0xC0);
+
+        memory.write_byte(HRAM_START as u16, 0x12);
+        assert_eq!(memory.read_byte(HRAM_START as u16), 0x12);
+
+        memory.tick(OAM_SIZE as u8);
+
+        assert_eq!(memory.read_byte(RAM0_START as u16), 0x00);
+        assert_eq!(memory.read_byte(OAM_START as u16 + 0x9F), 0x9F);
+    }
+
+    #[test]
+    fn vram_dma_transfers() {
+        let mut rom = rom(0x00);
+        rom[cartridge::constants::GBC_SUPPORT_TYPE] = 0x80;
+
+        let mut memory = Memory::new(rom, Model::Cgb).unwrap();
+
+        for i in 0..0x40 {
+            memory.write_byte((RAM0_START + i) as u16, i as u8 + 1);
+        }
+
+        let start = |memory: &mut Memory, control: u8| {
+            memory.write_byte(VRAM_DMA_SOURCE_HIGH as u16, 0xC0);
+            memory.write_byte(VRAM_DMA_SOURCE_LOW as u16, 0x00);
+            memory.write_byte(VRAM_DMA_DESTINATION_HIGH as u16, 0x01);
+            memory.write_byte(VRAM_DMA_DESTINATION_LOW as u16, 0x00);
+            memory.write_byte(VRAM_DMA_CONTROL as u16, control);
+        };
+
+        // A general purpose transfer of 2 blocks happens all at once.
+        start(&mut memory, 0x01);
+
+        assert_eq!(memory.read_byte(0x8100), 0x01);
+        assert_eq!(memory.read_byte(0x811F), 0x20);
+        assert_eq!(memory.read_byte(0x8120), 0x00);
+        assert_eq!(memory.read_byte(VRAM_DMA_CONTROL as u16), 0xFF);
+        assert_eq!(memory.take_stall_cycles(), 16);
+
+        // An H-Blank transfer of 4 blocks copies one block per H-Blank, until it's cancelled.
+        memory.write_byte(0x8100, 0x00);
+        start(&mut memory, 0x83);
+
+        assert_eq!(memory.read_byte(0x8100), 0x00);
+        assert_eq!(memory.read_byte(VRAM_DMA_CONTROL as u16), 0x03);
+
+        memory.enter_hblank();
+        memory.enter_hblank();
+
+        assert_eq!(memory.read_byte(0x8100), 0x01);
+        assert_eq!(memory.read_byte(0x811F), 0x20);
+        assert_eq!(memory.read_byte(VRAM_DMA_CONTROL as u16), 0x01);
+
+        memory.write_byte(VRAM_DMA_CONTROL as u16, 0x00);
+        memory.enter_hblank();
+
+        assert_eq!(memory.read_byte(0x8120), 0x00);
+        assert_eq!(memory.read_byte(VRAM_DMA_CONTROL as u16), 0x81);
+    }
+
+    #[test]
+    fn switches_banks() {
+        let mut rom = rom(0x00);
+        rom[cartridge::constants::GBC_SUPPORT_TYPE] = 0x80;
+
+        let mut memory = Memory::new(rom.clone(), Model::Cgb).unwrap();
+
+        // VRAM banks
+        memory.write_byte(0x8000, 0x11);
+        memory.write_byte(VRAM_BANK as u16, 0xFF);
+        assert_eq!(memory.read_byte(VRAM_BANK as u16), 0xFF);
+        assert_eq!(memory.read_byte(0x8000), 0x00);
+
+        memory.write_byte(0x8000, 0x22);
+        memory.write_byte(VRAM_BANK as u16, 0x00);
+        assert_eq!(memory.read_byte(VRAM_BANK as u16), 0xFE);
+        assert_eq!(memory.read_byte(0x8000), 0x11);
+
+        // WRAM banks, where bank 0 selects bank 1
+        memory.write_byte(0xD000, 0x33);
+        memory.write_byte(WRAM_BANK as u16, 0xFA);
+        assert_eq!(memory.read_byte(WRAM_BANK as u16), 0xFA);
+        assert_eq!(memory.read_byte(0xD000), 0x00);
+
+        memory.write_byte(WRAM_BANK as u16, 0x00);
+        assert_eq!(memory.read_byte(WRAM_BANK as u16), 0xF9);
+        assert_eq!(memory.read_byte(0xD000), 0x33);
+
+        // Echo RAM mirrors bank 0 and the selected bank.
+        memory.write_byte(0xC000, 0x44);
+        memory.write_byte(WRAM_BANK as u16, 0x02);
+        assert_eq!(memory.read_byte(0xE000), 0x44);
+        assert_eq!(memory.read_byte(0xF000), 0x00);
+
+        memory.write_byte(0xF000, 0x55);
+        assert_eq!(memory.read_byte(0xD000), 0x55);
+
+        // Neither register exists in Classic mode.
+        rom[cartridge::constants::GBC_SUPPORT_TYPE] = 0x00;
+        let mut memory = Memory::new(rom, Model::Cgb).unwrap();
+
+        memory.write_byte(VRAM_BANK as u16, 0x01);
+        memory.write_byte(WRAM_BANK as u16, 0x02);
+        memory.write_byte(0xD000, 0x66);
+
+        assert_eq!(memory.read_byte(VRAM_BANK as u16), 0xFF);
+        assert_eq!(memory.read_byte(WRAM_BANK as u16), 0xFF);
+        assert_eq!(memory.vram_bank, 0);
+        assert_eq!(memory.wram_bank, 1);
+    }
+}
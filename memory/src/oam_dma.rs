@@ -0,0 +1,89 @@
+use crate::constants::OAM_SIZE;
+
+/// The OAM DMA engine, controlled by writing the upper byte of a source address to
+/// [`OAM_DMA`](crate::constants::OAM_DMA).
+///
+/// A transfer copies [`OAM_SIZE`] bytes from the source into OAM, one byte per M-cycle, after a
+/// single cycle of setup. Writing to the register while a transfer is running restarts it with
+/// the new source; the old transfer keeps running during the new one's setup cycle.
+#[derive(Debug, Default)]
+pub struct OamDma {
+    register: u8,
+    active: Option<Transfer>,
+    pending: Option<Pending>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Transfer {
+    source: u16,
+    index: u16,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Pending {
+    source: u16,
+    delay: u8,
+}
+
+impl OamDma {
+    pub fn with_register(register: u8) -> Self {
+        Self {
+            register,
+            ..Default::default()
+        }
+    }
+
+    /// Returns `true` while bytes are being copied, during which the CPU can only access HRAM
+    /// and the IO registers.
+    pub fn is_active(&self) -> bool {
+        self.active.is_some()
+    }
+
+    pub fn read(&self) -> u8 {
+        self.register
+    }
+
+    pub fn write(&mut self, value: u8) {
+        self.register = value;
+
+        // Sources above $DFFF read from echo RAM, instead of OAM and the IO registers.
+        let page = if value >= 0xE0 { value - 0x20 } else { value };
+
+        self.pending = Some(Pending {
+            source: (page as u16) << 8,
+            // The write itself takes up the first cycle, followed by a cycle of setup.
+            delay: 2,
+        });
+    }
+
+    /// Advances the transfer by one M-cycle. Returns the source address and OAM offset of the
+    /// byte to copy during this cycle, if there is one.
+    pub fn tick(&mut self) -> Option<(u16, usize)> {
+        let copy = self
+            .active
+            .map(|transfer| (transfer.source + transfer.index, transfer.index as usize));
+
+        if let Some(transfer) = &mut self.active {
+            transfer.index += 1;
+
+            if transfer.index as usize == OAM_SIZE {
+                self.active = None;
+            }
+        }
+
+        if let Some(pending) = &mut self.pending {
+            pending.delay -= 1;
+
+            if pending.delay == 0 {
+                self.active = Some(Transfer {
+                    source: pending.source,
+                    index: 0,
+                });
+
+                self.pending = None;
+            }
+        }
+
+        copy
+    }
+}
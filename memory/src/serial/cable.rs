@@ -0,0 +1,89 @@
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+/// Something plugged into the other end of the link cable.
+///
+/// Transfers are exchanged a byte at a time. [`Serial`](super::Serial) takes care of shifting
+/// the received byte into `SB` a bit at a time, at the rate set by whichever side provides the
+/// clock.
+pub trait LinkCable: Send {
+    /// Starts a transfer clocked by this Game Boy, sending `byte` to the other end.
+    fn send(&mut self, byte: u8);
+
+    /// Returns the byte the other end sent back for the transfer started by the last call to
+    /// `send`, once it has arrived. This is `$FF` if nothing is listening.
+    ///
+    /// This is called on every M-cycle until the byte arrives, so it shouldn't block.
+    fn receive(&mut self) -> Option<u8>;
+
+    /// Checks whether the other end has started a transfer using its own clock, and if so sends
+    /// `byte` back and returns the byte it sent.
+    ///
+    /// This is only called while this Game Boy is waiting for a transfer using the external
+    /// clock, on every M-cycle, so it shouldn't block. A transfer the other end starts before
+    /// then should be kept until the next call.
+    fn poll(&mut self, _byte: u8) -> Option<u8> {
+        None
+    }
+}
+
+/// A link cable with nothing on the other end, so every transfer receives `$FF`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Disconnected;
+
+impl LinkCable for Disconnected {
+    fn send(&mut self, _byte: u8) {}
+
+    fn receive(&mut self) -> Option<u8> {
+        Some(0xFF)
+    }
+}
+
+/// Records every byte sent over the link cable, as if nothing were connected to the other end.
+///
+/// Clones share the same output, so a clone can be kept around to inspect what was sent after
+/// the original has been connected.
+#[derive(Debug, Default, Clone)]
+pub struct Capture {
+    output: Arc<Mutex<Vec<u8>>>,
+    echo: bool,
+}
+
+impl Capture {
+    /// Creates a capture that also writes each byte to stdout as it's sent.
+    pub fn stdout() -> Self {
+        Self {
+            echo: true,
+            ..Default::default()
+        }
+    }
+
+    /// Returns every byte sent so far.
+    pub fn output(&self) -> Vec<u8> {
+        self.output.lock().unwrap().clone()
+    }
+
+    /// Returns the number of bytes sent so far.
+    pub fn len(&self) -> usize {
+        self.output.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl LinkCable for Capture {
+    fn send(&mut self, byte: u8) {
+        self.output.lock().unwrap().push(byte);
+
+        if self.echo {
+            let mut stdout = io::stdout().lock();
+            let _ = stdout.write_all(&[byte]).and_then(|_| stdout.flush());
+        }
+    }
+
+    fn receive(&mut self) -> Option<u8> {
+        Some(0xFF)
+    }
+}
@@ -0,0 +1,170 @@
+use super::LinkCable;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+
+/// One end of an in-process link cable, connecting two emulated Game Boys.
+///
+/// A transfer clocked by one end doesn't receive anything until the other end is ready for it
+/// and responds, so [`Serial`](super::Serial) holds off shifting until then. This keeps both ends
+/// in step a byte at a time, however fast each of them is running, without either end blocking,
+/// so each end is meant to be driven from its own thread. The transfer only receives `$FF` if the
+/// other end has been dropped.
+#[derive(Debug)]
+pub struct Loopback {
+    pub(super) sender: Sender<Message>,
+    pub(super) receiver: Receiver<Message>,
+
+    /// The number of transfers sent that haven't been answered yet. Only the answer to the latest
+    /// one is received; the rest belong to transfers that were abandoned by writing to `SC`.
+    unanswered: usize,
+}
+
+/// A message sent from one end of the cable to the other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Message {
+    /// Starts a transfer clocked by the sender.
+    Transfer(u8),
+
+    /// Responds to a transfer started by the other end.
+    Reply(u8),
+}
+
+impl Message {
+    pub(super) fn encode(self) -> [u8; 2] {
+        match self {
+            Self::Transfer(byte) => [0x01, byte],
+            Self::Reply(byte) => [0x02, byte],
+        }
+    }
+
+    pub(super) fn decode(bytes: [u8; 2]) -> Option<Self> {
+        match bytes {
+            [0x01, byte] => Some(Self::Transfer(byte)),
+            [0x02, byte] => Some(Self::Reply(byte)),
+            _ => None,
+        }
+    }
+}
+
+impl Loopback {
+    /// Creates both ends of a cable.
+    pub fn pair() -> (Self, Self) {
+        let (a_sender, b_receiver) = mpsc::channel();
+        let (b_sender, a_receiver) = mpsc::channel();
+
+        let a = Self {
+            sender: a_sender,
+            receiver: a_receiver,
+            unanswered: 0,
+        };
+
+        let b = Self {
+            sender: b_sender,
+            receiver: b_receiver,
+            unanswered: 0,
+        };
+
+        (a, b)
+    }
+}
+
+impl LinkCable for Loopback {
+    fn send(&mut self, byte: u8) {
+        if self.sender.send(Message::Transfer(byte)).is_ok() {
+            self.unanswered += 1;
+        }
+    }
+
+    fn receive(&mut self) -> Option<u8> {
+        loop {
+            let byte = match self.receiver.try_recv() {
+                Ok(Message::Reply(reply)) => reply,
+                // Both ends started a transfer at the same time, so each receives the other's byte.
+                Ok(Message::Transfer(other)) => other,
+                Err(TryRecvError::Empty) => return None,
+                Err(TryRecvError::Disconnected) => {
+                    self.unanswered = 0;
+                    return Some(0xFF);
+                }
+            };
+
+            self.unanswered = self.unanswered.saturating_sub(1);
+
+            if self.unanswered == 0 {
+                return Some(byte);
+            }
+        }
+    }
+
+    fn poll(&mut self, byte: u8) -> Option<u8> {
+        // Answers to abandoned transfers come first, and aren't meant for this one.
+        while self.unanswered > 0 {
+            self.receiver.try_recv().ok()?;
+            self.unanswered -= 1;
+        }
+
+        match self.receiver.try_recv() {
+            Ok(Message::Transfer(received)) => {
+                let _ = self.sender.send(Message::Reply(byte));
+                Some(received)
+            }
+            // Every transfer sent from this end has been answered, so there's never a reply here.
+            Ok(Message::Reply(_)) | Err(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exchanges_bytes() {
+        let (mut a, mut b) = Loopback::pair();
+
+        a.send(0x12);
+        assert_eq!(b.poll(0x34), Some(0x12));
+        assert_eq!(a.receive(), Some(0x34));
+        assert_eq!(b.poll(0x34), None);
+    }
+
+    #[test]
+    fn waits_for_the_other_end() {
+        let (mut a, mut b) = Loopback::pair();
+
+        // However long the other end takes to be ready, the transfer still goes through.
+        a.send(0x12);
+        assert_eq!(a.receive(), None);
+        assert_eq!(a.receive(), None);
+
+        assert_eq!(b.poll(0x34), Some(0x12));
+        assert_eq!(a.receive(), Some(0x34));
+
+        // The other end has been dropped.
+        drop(b);
+        a.send(0x56);
+        assert_eq!(a.receive(), Some(0xFF));
+    }
+
+    #[test]
+    fn skips_answers_to_abandoned_transfers() {
+        let (mut a, mut b) = Loopback::pair();
+
+        a.send(0x12);
+        a.send(0x56);
+        assert_eq!(b.poll(0x34), Some(0x12));
+        assert_eq!(a.receive(), None);
+
+        assert_eq!(b.poll(0x78), Some(0x56));
+        assert_eq!(a.receive(), Some(0x78));
+    }
+
+    #[test]
+    fn simultaneous_transfers_swap_bytes() {
+        let (mut a, mut b) = Loopback::pair();
+
+        a.send(0x12);
+        b.send(0x34);
+        assert_eq!(a.receive(), Some(0x34));
+        assert_eq!(b.receive(), Some(0x12));
+    }
+}
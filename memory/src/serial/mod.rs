@@ -0,0 +1,244 @@
+use crate::constants::{SERIAL_CONTROL, SERIAL_DATA};
+use gb_rs_common::DeviceMode;
+
+pub use cable::{Capture, Disconnected, LinkCable};
+pub use loopback::Loopback;
+pub use socket::{LinkAddress, Socket};
+
+mod cable;
+mod loopback;
+mod socket;
+
+/// The serial port, controlled by `SB` ([`SERIAL_DATA`]) and `SC` ([`SERIAL_CONTROL`]).
+///
+/// A transfer is started by setting bit 7 of `SC`, and shifts the 8 bits of `SB` out over the
+/// link cable while shifting the other end's byte in, most significant bit first. Whichever side
+/// has bit 0 of `SC` set provides the clock:
+/// - The internal clock shifts a bit on every falling edge of bit 8 of the system counter (i.e.
+///   8192 times a second), or bit 3 if the fast clock (bit 1 of `SC`) is selected in Color mode.
+///   Shifting only begins once the other end's byte has arrived over the cable, which never
+///   blocks emulation.
+/// - The external clock is provided by the other end, so the transfer completes whenever it
+///   decides to start one.
+///
+/// Either way, bit 7 of `SC` is cleared and the serial interrupt is requested once all 8 bits
+/// have been shifted.
+pub struct Serial {
+    data: u8,
+    control: u8,
+    mode: DeviceMode,
+
+    /// The byte being received during a transfer using the internal clock, once it has arrived,
+    /// and the number of bits of it that are still to be shifted into `SB`.
+    incoming: Option<u8>,
+    remaining: u8,
+
+    /// The state of the clock signal on the previous M-cycle.
+    signal: bool,
+    cable: Box<dyn LinkCable>,
+}
+
+impl Serial {
+    pub fn new(mode: DeviceMode) -> Self {
+        Self {
+            data: 0,
+            control: 0,
+            mode,
+            incoming: None,
+            remaining: 0,
+            signal: false,
+            cable: Box::new(Disconnected),
+        }
+    }
+
+    /// Replaces the link cable, returning the one that was connected before.
+    pub fn connect(&mut self, cable: Box<dyn LinkCable>) -> Box<dyn LinkCable> {
+        std::mem::replace(&mut self.cable, cable)
+    }
+
+    /// Advances the serial port by one M-cycle, given the value of the system counter that also
+    /// drives the timer. Returns `true` if the serial interrupt should be requested.
+    pub fn tick(&mut self, counter: u16) -> bool {
+        let bit = if self.is_fast() { 3 } else { 8 };
+        let signal = counter & (1 << bit) != 0;
+        let falling_edge = self.signal && !signal;
+        self.signal = signal;
+
+        match self.control & 0x81 {
+            // Transferring using the internal clock
+            0x81 => {
+                if self.remaining == 0 {
+                    return false;
+                }
+
+                if self.incoming.is_none() {
+                    self.incoming = self.cable.receive();
+                }
+
+                let incoming = match self.incoming {
+                    Some(incoming) if falling_edge => incoming,
+                    _ => return false,
+                };
+
+                self.data = self.data << 1 | incoming >> 7;
+                self.incoming = Some(incoming << 1);
+                self.remaining -= 1;
+
+                if self.remaining == 0 {
+                    self.control &= 0x7F;
+                    return true;
+                }
+
+                false
+            }
+            // Waiting for the other end to start a transfer. A transfer it starts before then
+            // waits in the cable, rather than being answered here.
+            0x80 => match self.cable.poll(self.data) {
+                Some(byte) => {
+                    self.data = byte;
+                    self.control &= 0x7F;
+
+                    true
+                }
+                None => false,
+            },
+            _ => false,
+        }
+    }
+
+    pub fn read(&self, address: usize) -> u8 {
+        match address {
+            SERIAL_DATA => self.data,
+            SERIAL_CONTROL => self.control | self.read_mask(),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn write(&mut self, address: usize, value: u8) {
+        match address {
+            SERIAL_DATA => self.data = value,
+            SERIAL_CONTROL => {
+                self.control = value & !self.read_mask();
+                self.incoming = None;
+                self.remaining = 0;
+
+                if self.control & 0x81 == 0x81 {
+                    self.cable.send(self.data);
+                    self.remaining = 8;
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn is_fast(&self) -> bool {
+        self.control & 0x02 != 0
+    }
+
+    /// Returns the bits of `SC` that always read as 1. The fast clock bit only exists in Color
+    /// mode.
+    fn read_mask(&self) -> u8 {
+        match self.mode {
+            DeviceMode::Color => 0x7C,
+            DeviceMode::Classic => 0x7E,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ticks the serial port `count` times, incrementing `counter` like the timer does.
+    fn tick_n(serial: &mut Serial, counter: &mut u16, count: usize) -> bool {
+        (0..count).fold(false, |interrupt, _| {
+            *counter = counter.wrapping_add(4);
+            serial.tick(*counter) | interrupt
+        })
+    }
+
+    #[test]
+    fn transfers_at_clock_rate() {
+        let capture = Capture::default();
+        let mut serial = Serial::new(DeviceMode::Classic);
+        let mut counter = 0;
+
+        serial.connect(Box::new(capture.clone()));
+        serial.write(SERIAL_DATA, 0x42);
+        serial.write(SERIAL_CONTROL, 0x81);
+
+        assert_eq!(capture.output(), [0x42]);
+
+        // A bit is shifted every 128 M-cycles, and nothing is connected to the other end.
+        assert!(!tick_n(&mut serial, &mut counter, 128 * 4));
+        assert_eq!(serial.read(SERIAL_DATA), 0x2F);
+        assert_eq!(serial.read(SERIAL_CONTROL), 0xFF);
+
+        assert!(tick_n(&mut serial, &mut counter, 128 * 4));
+        assert_eq!(serial.read(SERIAL_DATA), 0xFF);
+        assert_eq!(serial.read(SERIAL_CONTROL), 0x7F);
+    }
+
+    #[test]
+    fn fast_clock_is_color_only() {
+        let mut serial = Serial::new(DeviceMode::Color);
+        let mut counter = 0;
+
+        serial.write(SERIAL_CONTROL, 0x83);
+        assert_eq!(serial.read(SERIAL_CONTROL), 0xFF);
+        assert!(tick_n(&mut serial, &mut counter, 4 * 8));
+
+        let mut serial = Serial::new(DeviceMode::Classic);
+
+        serial.write(SERIAL_CONTROL, 0x83);
+        assert_eq!(serial.read(SERIAL_CONTROL), 0xFF);
+        assert!(!tick_n(&mut serial, &mut counter, 4 * 8));
+    }
+
+    #[test]
+    fn internal_clock_waits_for_reply() {
+        let (mut remote, local) = Loopback::pair();
+        let mut serial = Serial::new(DeviceMode::Classic);
+        let mut counter = 0;
+
+        serial.connect(Box::new(local));
+        serial.write(SERIAL_DATA, 0x12);
+        serial.write(SERIAL_CONTROL, 0x81);
+
+        // Nothing is shifted until the other end is ready, however long that takes.
+        assert!(!tick_n(&mut serial, &mut counter, 128 * 16));
+        assert_eq!(serial.read(SERIAL_DATA), 0x12);
+        assert_eq!(serial.read(SERIAL_CONTROL), 0xFF);
+
+        assert_eq!(remote.poll(0x34), Some(0x12));
+        assert!(tick_n(&mut serial, &mut counter, 128 * 8));
+        assert_eq!(serial.read(SERIAL_DATA), 0x34);
+        assert_eq!(serial.read(SERIAL_CONTROL), 0x7F);
+    }
+
+    #[test]
+    fn external_clock_waits_to_be_armed() {
+        let (remote, local) = Loopback::pair();
+        let mut serial = Serial::new(DeviceMode::Classic);
+        let mut counter = 0;
+
+        serial.connect(Box::new(local));
+        serial.write(SERIAL_DATA, 0x34);
+
+        // The other end starts its transfer first, which is held until this end is ready.
+        remote
+            .sender
+            .send(loopback::Message::Transfer(0x12))
+            .unwrap();
+        assert!(!tick_n(&mut serial, &mut counter, 16));
+        assert!(remote.receiver.try_recv().is_err());
+
+        serial.write(SERIAL_CONTROL, 0x80);
+        assert!(tick_n(&mut serial, &mut counter, 1));
+        assert_eq!(serial.read(SERIAL_DATA), 0x12);
+        assert_eq!(
+            remote.receiver.try_recv(),
+            Ok(loopback::Message::Reply(0x34))
+        );
+    }
+}
@@ -0,0 +1,255 @@
+use super::loopback::Message;
+use super::{LinkCable, Loopback};
+use std::fmt::Display;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::str::FromStr;
+use std::thread;
+
+#[cfg(unix)]
+use std::fs;
+#[cfg(unix)]
+use std::os::unix::fs::FileTypeExt;
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
+
+/// A link cable connected to another emulator process, over TCP or a Unix domain socket.
+///
+/// Each transfer is sent as a 2-byte message. Just like a [`Loopback`], the end providing the
+/// clock waits for the other end to be ready and reply before shifting in the byte it received,
+/// and the end using the external clock only completes its transfer once the other end starts
+/// one. This keeps both ends in sync a byte at a time, regardless of how fast either emulator is
+/// running.
+///
+/// The connection is closed once the socket is dropped. After that, every transfer receives `$FF`
+/// as if nothing were connected.
+#[derive(Debug)]
+pub struct Socket {
+    link: Loopback,
+}
+
+impl Socket {
+    /// Waits for another emulator to connect to `address`.
+    ///
+    /// A Unix domain socket left behind by an earlier run is replaced, and the socket file is
+    /// removed again once the other emulator has connected, since nothing else can connect to it.
+    pub fn listen(address: &LinkAddress) -> io::Result<Self> {
+        match address {
+            LinkAddress::Tcp(address) => {
+                let (stream, _) = TcpListener::bind(address)?.accept()?;
+                Self::from_tcp(stream)
+            }
+            #[cfg(unix)]
+            LinkAddress::Unix(path) => {
+                remove_stale_socket(path)?;
+
+                let listener = UnixListener::bind(path)?;
+                let accepted = listener.accept();
+                let _ = fs::remove_file(path);
+
+                Self::from_unix(accepted?.0)
+            }
+        }
+    }
+
+    /// Connects to another emulator that's listening on `address`.
+    pub fn connect(address: &LinkAddress) -> io::Result<Self> {
+        match address {
+            LinkAddress::Tcp(address) => Self::from_tcp(TcpStream::connect(address)?),
+            #[cfg(unix)]
+            LinkAddress::Unix(path) => Self::from_unix(UnixStream::connect(path)?),
+        }
+    }
+
+    pub fn from_tcp(stream: TcpStream) -> io::Result<Self> {
+        // Every message is tiny and waited on, so there's no point in batching them up.
+        stream.set_nodelay(true)?;
+
+        let control = stream.try_clone()?;
+        let shutdown = move || {
+            let _ = control.shutdown(Shutdown::Both);
+        };
+
+        Ok(Self::bridge(stream.try_clone()?, stream, shutdown))
+    }
+
+    #[cfg(unix)]
+    pub fn from_unix(stream: UnixStream) -> io::Result<Self> {
+        let control = stream.try_clone()?;
+        let shutdown = move || {
+            let _ = control.shutdown(Shutdown::Both);
+        };
+
+        Ok(Self::bridge(stream.try_clone()?, stream, shutdown))
+    }
+
+    /// Connects one end of a [`Loopback`] to a stream, with a thread forwarding messages in each
+    /// direction. Both threads exit once either the stream or the cable is closed, and the stream
+    /// is shut down with `shutdown` once the cable is closed, so the other end sees it hang up.
+    fn bridge<R, W, S>(mut reader: R, mut writer: W, shutdown: S) -> Self
+    where
+        R: Read + Send + 'static,
+        W: Write + Send + 'static,
+        S: FnOnce() + Send + 'static,
+    {
+        let (link, remote) = Loopback::pair();
+        let Loopback {
+            sender, receiver, ..
+        } = remote;
+
+        thread::spawn(move || {
+            let mut bytes = [0; 2];
+
+            while reader.read_exact(&mut bytes).is_ok() {
+                let message = match Message::decode(bytes) {
+                    Some(message) => message,
+                    None => break,
+                };
+
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+
+        thread::spawn(move || {
+            for message in receiver {
+                if writer.write_all(&message.encode()).is_err() {
+                    break;
+                }
+            }
+
+            // The reader thread also holds onto the stream, so it has to be shut down explicitly.
+            shutdown();
+        });
+
+        Self { link }
+    }
+}
+
+impl LinkCable for Socket {
+    fn send(&mut self, byte: u8) {
+        self.link.send(byte)
+    }
+
+    fn receive(&mut self) -> Option<u8> {
+        self.link.receive()
+    }
+
+    fn poll(&mut self, byte: u8) -> Option<u8> {
+        self.link.poll(byte)
+    }
+}
+
+/// Removes the socket file at `path`, if there is one. Anything else there is left alone, so
+/// binding fails instead of deleting a file by mistake.
+#[cfg(unix)]
+fn remove_stale_socket(path: &Path) -> io::Result<()> {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => fs::remove_file(path),
+        Ok(_) => Ok(()),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(error) => Err(error),
+    }
+}
+
+/// Where a [`Socket`] listens or connects.
+///
+/// This is parsed from either `unix:<path>` for a Unix domain socket, or `<host>:<port>` for
+/// TCP.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkAddress {
+    Tcp(String),
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+impl FromStr for LinkAddress {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        #[cfg(unix)]
+        if let Some(path) = s.strip_prefix("unix:") {
+            return Ok(Self::Unix(path.into()));
+        }
+
+        Ok(Self::Tcp(s.to_owned()))
+    }
+}
+
+impl Display for LinkAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tcp(address) => write!(f, "{}", address),
+            #[cfg(unix)]
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn links_over_tcp() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = LinkAddress::Tcp(listener.local_addr().unwrap().to_string());
+
+        let server = thread::spawn(move || {
+            let mut socket = Socket::from_tcp(listener.accept().unwrap().0).unwrap();
+
+            loop {
+                if let Some(received) = socket.poll(0x34) {
+                    return received;
+                }
+            }
+        });
+
+        let mut socket = Socket::connect(&address).unwrap();
+
+        socket.send(0x12);
+        assert_eq!(server.join().unwrap(), 0x12);
+        assert_eq!(receive(&mut socket), 0x34);
+
+        // The other end has hung up, so there's nothing to reply.
+        socket.send(0x56);
+        assert_eq!(receive(&mut socket), 0xFF);
+    }
+
+    /// Waits for the reply to the transfer started by the last call to `send`.
+    fn receive(socket: &mut Socket) -> u8 {
+        loop {
+            if let Some(received) = socket.receive() {
+                return received;
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn replaces_stale_unix_socket() {
+        let path = std::env::temp_dir().join(format!("gb-rs-link-{}.sock", std::process::id()));
+        let address = LinkAddress::Unix(path.clone());
+
+        // A listener that goes away without cleaning up leaves its socket file behind.
+        drop(UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+
+        let server = thread::spawn({
+            let address = address.clone();
+            move || Socket::listen(&address).map(drop)
+        });
+
+        let _socket = loop {
+            if let Ok(socket) = Socket::connect(&address) {
+                break socket;
+            }
+        };
+
+        server.join().unwrap().unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,199 @@
+use crate::constants::{DIVIDER, TIMER_CONTROL, TIMER_COUNTER, TIMER_MODULO};
+
+/// The divider (`DIV`) and timer (`TIMA`, `TMA` and `TAC`) registers.
+///
+/// Internally, both are driven by a single 16-bit counter that increments every T-cycle, of which
+/// `DIV` is the upper 8 bits. `TIMA` is incremented whenever the counter bit selected by `TAC`
+/// (ANDed with the timer enable bit) goes from 1 to 0. Since this is an edge detector and not a
+/// true clock, resetting `DIV` or changing `TAC` can also trigger an increment.
+///
+/// See https://gbdev.io/pandocs/Timer_Obscure_Behaviour.html.
+#[derive(Debug, Default)]
+pub struct Timer {
+    counter: u16,
+    tima: u8,
+    tma: u8,
+    tac: u8,
+
+    /// Set for the cycle after `TIMA` overflows. `TIMA` reads as `$00` during this cycle, and the
+    /// reload (and interrupt) can be cancelled by writing to `TIMA`.
+    overflowed: bool,
+
+    /// Set for the cycle during which `TIMA` is reloaded from `TMA`. Writes to `TIMA` are ignored
+    /// during this cycle, and writes to `TMA` are also copied into `TIMA`.
+    reloading: bool,
+}
+
+impl Timer {
+    /// Creates a timer with `DIV` set to `divider`, and everything else cleared.
+    pub fn with_divider(divider: u8) -> Self {
+        Self {
+            counter: (divider as u16) << 8,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the 16-bit system counter, of which `DIV` is the upper byte.
+    pub fn counter(&self) -> u16 {
+        self.counter
+    }
+
+    /// Advances the timer by one M-cycle. Returns `true` if the timer interrupt should be
+    /// requested.
+    pub fn tick(&mut self) -> bool {
+        self.reloading = false;
+
+        let interrupt = if self.overflowed {
+            self.overflowed = false;
+            self.reloading = true;
+            self.tima = self.tma;
+
+            true
+        } else {
+            false
+        };
+
+        let signal = self.signal();
+        self.counter = self.counter.wrapping_add(4);
+        self.detect_falling_edge(signal);
+
+        interrupt
+    }
+
+    pub fn read(&self, address: usize) -> u8 {
+        match address {
+            DIVIDER => (self.counter >> 8) as u8,
+            TIMER_COUNTER => self.tima,
+            TIMER_MODULO => self.tma,
+            TIMER_CONTROL => 0xF8 | self.tac,
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn write(&mut self, address: usize, value: u8) {
+        match address {
+            DIVIDER => {
+                let signal = self.signal();
+                self.counter = 0;
+                self.detect_falling_edge(signal);
+            }
+            TIMER_COUNTER => {
+                if !self.reloading {
+                    self.tima = value;
+                    self.overflowed = false;
+                }
+            }
+            TIMER_MODULO => {
+                self.tma = value;
+
+                if self.reloading {
+                    self.tima = value;
+                }
+            }
+            TIMER_CONTROL => {
+                let signal = self.signal();
+                self.tac = value & 0x07;
+                self.detect_falling_edge(signal);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns the state of the counter bit selected by `TAC`, ANDed with the timer enable bit.
+    fn signal(&self) -> bool {
+        let bit = match self.tac & 0x03 {
+            0 => 9,
+            1 => 3,
+            2 => 5,
+            _ => 7,
+        };
+
+        self.tac & 0x04 != 0 && self.counter & (1 << bit) != 0
+    }
+
+    fn detect_falling_edge(&mut self, previous: bool) {
+        if previous && !self.signal() {
+            self.increment();
+        }
+    }
+
+    fn increment(&mut self) {
+        let (tima, overflowed) = self.tima.overflowing_add(1);
+
+        self.tima = tima;
+        self.overflowed |= overflowed;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a timer that's enabled and increments every 4 M-cycles.
+    fn fast_timer() -> Timer {
+        let mut timer = Timer::default();
+        timer.write(TIMER_CONTROL, 0x05);
+
+        timer
+    }
+
+    fn tick_n(timer: &mut Timer, count: usize) -> bool {
+        (0..count).fold(false, |interrupt, _| timer.tick() | interrupt)
+    }
+
+    #[test]
+    fn counts_on_falling_edges() {
+        let mut timer = fast_timer();
+
+        tick_n(&mut timer, 16);
+
+        assert_eq!(timer.read(TIMER_COUNTER), 4);
+        assert_eq!(timer.read(DIVIDER), 0);
+
+        tick_n(&mut timer, 48);
+
+        assert_eq!(timer.read(DIVIDER), 1);
+    }
+
+    #[test]
+    fn divider_reset_can_increment() {
+        let mut timer = fast_timer();
+
+        // Bit 3 of the counter is now set, so resetting it causes a falling edge.
+        tick_n(&mut timer, 2);
+        timer.write(DIVIDER, 0x12);
+
+        assert_eq!(timer.read(TIMER_COUNTER), 1);
+        assert_eq!(timer.read(DIVIDER), 0);
+    }
+
+    #[test]
+    fn reload_is_delayed_by_a_cycle() {
+        let mut timer = fast_timer();
+        timer.write(TIMER_MODULO, 0xAB);
+        timer.write(TIMER_COUNTER, 0xFF);
+
+        assert!(!tick_n(&mut timer, 4));
+        assert_eq!(timer.read(TIMER_COUNTER), 0x00);
+
+        assert!(timer.tick());
+        assert_eq!(timer.read(TIMER_COUNTER), 0xAB);
+
+        // Writes to TIMA are ignored on the cycle it's reloaded.
+        timer.write(TIMER_COUNTER, 0x12);
+        assert_eq!(timer.read(TIMER_COUNTER), 0xAB);
+    }
+
+    #[test]
+    fn writing_tima_cancels_reload() {
+        let mut timer = fast_timer();
+        timer.write(TIMER_MODULO, 0xAB);
+        timer.write(TIMER_COUNTER, 0xFF);
+
+        tick_n(&mut timer, 4);
+        timer.write(TIMER_COUNTER, 0x12);
+
+        assert!(!timer.tick());
+        assert_eq!(timer.read(TIMER_COUNTER), 0x12);
+    }
+}
@@ -0,0 +1,120 @@
+use crate::constants::{
+    VRAM_DMA_CONTROL, VRAM_DMA_DESTINATION_HIGH, VRAM_DMA_DESTINATION_LOW, VRAM_DMA_SOURCE_HIGH,
+    VRAM_DMA_SOURCE_LOW, VRAM_START,
+};
+
+/// The number of bytes copied by a VRAM DMA transfer at a time.
+pub const BLOCK_SIZE: u16 = 0x10;
+
+/// The CGB's VRAM DMA engine, controlled by `HDMA1-HDMA5` (`$FF51-$FF55`).
+///
+/// Transfers copy data into VRAM in blocks of [`BLOCK_SIZE`] bytes, in one of two modes:
+/// - General purpose DMA copies every block at once, pausing the CPU until it's done.
+/// - H-Blank DMA copies a single block at the start of each H-Blank, pausing the CPU while it
+///   does so. It can be cancelled by writing to `HDMA5` with bit 7 cleared.
+///
+/// This only tracks the registers and transfer progress; [`Memory`](crate::Memory) does the
+/// actual copying, since it needs access to the whole memory map.
+///
+/// H-Blank transfers only make progress when something calls
+/// [`Memory::enter_hblank`](crate::Memory::enter_hblank), which is the PPU's job. There's no PPU
+/// yet, so for now they stay armed (with `HDMA5` counting nothing down) until they're cancelled.
+#[derive(Debug)]
+pub struct VramDma {
+    source: u16,
+    destination: u16,
+
+    /// The number of blocks left to copy, minus one, as read back from `HDMA5`.
+    remaining: u8,
+    mode: Option<Mode>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    General,
+    HBlank,
+}
+
+impl Default for VramDma {
+    fn default() -> Self {
+        Self {
+            source: 0,
+            destination: 0,
+            remaining: 0x7F,
+            mode: None,
+        }
+    }
+}
+
+impl VramDma {
+    /// Returns the mode of the transfer in progress, if there is one.
+    pub fn mode(&self) -> Option<Mode> {
+        self.mode
+    }
+
+    pub fn read(&self, address: usize) -> u8 {
+        match address {
+            // Bit 7 reads back as 0 while an H-Blank transfer is in progress.
+            VRAM_DMA_CONTROL => match self.mode {
+                Some(_) => self.remaining,
+                None => 0x80 | self.remaining,
+            },
+            _ => 0xFF,
+        }
+    }
+
+    /// Writes to one of the VRAM DMA registers. Returns the mode of the transfer that was
+    /// started, if any.
+    pub fn write(&mut self, address: usize, value: u8) -> Option<Mode> {
+        match address {
+            VRAM_DMA_SOURCE_HIGH => self.source = (self.source & 0x00FF) | (value as u16) << 8,
+            VRAM_DMA_SOURCE_LOW => self.source = (self.source & 0xFF00) | (value & 0xF0) as u16,
+            VRAM_DMA_DESTINATION_HIGH => {
+                self.destination = (self.destination & 0x00FF) | ((value & 0x1F) as u16) << 8
+            }
+            VRAM_DMA_DESTINATION_LOW => {
+                self.destination = (self.destination & 0xFF00) | (value & 0xF0) as u16
+            }
+            VRAM_DMA_CONTROL => {
+                // Clearing bit 7 during an H-Blank transfer cancels it, instead of starting a
+                // general purpose transfer.
+                if self.mode == Some(Mode::HBlank) && value & 0x80 == 0 {
+                    self.mode = None;
+                    return None;
+                }
+
+                self.remaining = value & 0x7F;
+                self.mode = Some(if value & 0x80 == 0 {
+                    Mode::General
+                } else {
+                    Mode::HBlank
+                });
+
+                return self.mode;
+            }
+            _ => unreachable!(),
+        };
+
+        None
+    }
+
+    /// Advances the transfer by one block, returning the source and destination addresses of
+    /// the block to copy. Returns `None` if no transfer is in progress.
+    pub fn next_block(&mut self) -> Option<(u16, u16)> {
+        self.mode?;
+
+        let block = (self.source, VRAM_START as u16 | self.destination);
+
+        self.source = self.source.wrapping_add(BLOCK_SIZE);
+        self.destination = (self.destination + BLOCK_SIZE) & 0x1FF0;
+
+        if self.remaining == 0 {
+            self.remaining = 0x7F;
+            self.mode = None;
+        } else {
+            self.remaining -= 1;
+        }
+
+        Some(block)
+    }
+}